@@ -0,0 +1,206 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub day: u8,
+    pub part: u8,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub part: u8,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+type Part<'a> = &'a dyn Fn() -> Result<Answer, ParseError>;
+
+fn measure(runs: usize, f: Part) -> Result<Option<(Duration, Duration, Duration)>, ParseError> {
+    let mut durations = vec![];
+    for _ in 0..runs {
+        let start = Instant::now();
+        let answer = f()?;
+        durations.push(start.elapsed());
+        if answer == Answer::Unsolved {
+            return Ok(None);
+        }
+    }
+    durations.sort();
+    Ok(Some((
+        durations[0],
+        durations[durations.len() / 2],
+        durations[durations.len() - 1],
+    )))
+}
+
+// Unsolved parts are left out
+pub fn bench(solution: &dyn Solution, input: &str, runs: usize) -> Result<Vec<Timing>, ParseError> {
+    let parts: [(u8, Part); 2] = [
+        (1, &|| solution.part1(input)),
+        (2, &|| solution.part2(input)),
+    ];
+    let mut timings = vec![];
+    for (part, f) in parts.iter() {
+        if let Some((min, median, max)) = measure(runs, *f)? {
+            timings.push(Timing {
+                day: solution.day(),
+                part: *part,
+                min,
+                median,
+                max,
+            });
+        }
+    }
+    Ok(timings)
+}
+
+pub fn to_csv(timings: &[Timing]) -> String {
+    let mut csv = String::from("day,part,min_ns,median_ns,max_ns\n");
+    for t in timings {
+        csv += &format!(
+            "{},{},{},{},{}\n",
+            t.day,
+            t.part,
+            t.min.as_nanos(),
+            t.median.as_nanos(),
+            t.max.as_nanos()
+        );
+    }
+    csv
+}
+
+// One object per line, so `parse_report` can read it back without a JSON parser
+pub fn to_json(timings: &[Timing]) -> String {
+    let rows = timings
+        .iter()
+        .map(|t| {
+            format!(
+                "  {{\"day\": {}, \"part\": {}, \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+                t.day,
+                t.part,
+                t.min.as_nanos(),
+                t.median.as_nanos(),
+                t.max.as_nanos()
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+fn timing_from_fields(fields: &[&str]) -> Result<Timing, String> {
+    let number = |i: usize| -> Result<u64, String> {
+        let field = fields.get(i).ok_or("Missing field")?.trim();
+        field
+            .parse()
+            .map_err(|_| format!("Invalid number {}", field))
+    };
+    Ok(Timing {
+        day: number(0)? as u8,
+        part: number(1)? as u8,
+        min: Duration::from_nanos(number(2)?),
+        median: Duration::from_nanos(number(3)?),
+        max: Duration::from_nanos(number(4)?),
+    })
+}
+
+// Reads back the output of either `to_csv` or `to_json`
+pub fn parse_report(report: &str) -> Result<Vec<Timing>, String> {
+    if report.trim_start().starts_with('[') {
+        report
+            .lines()
+            .filter(|line| line.contains('{'))
+            .map(|line| {
+                let values = line
+                    .split(',')
+                    .map(|field| {
+                        field
+                            .split(':')
+                            .nth(1)
+                            .unwrap_or("")
+                            .trim_matches(|c| c == ' ' || c == '}')
+                    })
+                    .collect::<Vec<_>>();
+                timing_from_fields(&values)
+            })
+            .collect()
+    } else {
+        report
+            .lines()
+            .skip(1)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| timing_from_fields(&line.split(',').collect::<Vec<_>>()))
+            .collect()
+    }
+}
+
+// Parts whose median got slower than the baseline by more than `threshold` percent
+pub fn compare(current: &[Timing], baseline: &[Timing], threshold: f64) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|t| {
+            let base = baseline
+                .iter()
+                .find(|b| b.day == t.day && b.part == t.part)?;
+            let limit = base.median.as_secs_f64() * (1. + threshold / 100.);
+            if t.median.as_secs_f64() > limit {
+                Some(Regression {
+                    day: t.day,
+                    part: t.part,
+                    baseline: base.median,
+                    current: t.median,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn timing(day: u8, part: u8, median_ms: u64) -> Timing {
+    Timing {
+        day,
+        part,
+        min: Duration::from_millis(median_ms - 1),
+        median: Duration::from_millis(median_ms),
+        max: Duration::from_millis(median_ms + 1),
+    }
+}
+
+#[test]
+fn test_report_round_trip() {
+    let timings = vec![timing(4, 1, 120), timing(10, 2, 35)];
+    assert_eq!(parse_report(&to_csv(&timings)), Ok(timings.clone()));
+    assert_eq!(parse_report(&to_json(&timings)), Ok(timings));
+    assert!(parse_report("day,part\n1,x,1,2,3").is_err());
+}
+
+#[test]
+fn test_compare() {
+    let baseline = vec![timing(4, 1, 100), timing(10, 1, 100)];
+    let current = vec![timing(4, 1, 109), timing(10, 1, 111), timing(11, 1, 500)];
+    assert_eq!(
+        compare(&current, &baseline, 10.),
+        vec![Regression {
+            day: 10,
+            part: 1,
+            baseline: Duration::from_millis(100),
+            current: Duration::from_millis(111),
+        }]
+    );
+}
+
+#[test]
+fn test_bench_skips_unsolved() {
+    use crate::day12::Day12;
+
+    let timings = bench(&Day12, Day12.input(), 1).unwrap();
+    assert_eq!(timings.len(), 1);
+    assert_eq!((timings[0].day, timings[0].part), (12, 1));
+}
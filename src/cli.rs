@@ -0,0 +1,473 @@
+pub const USAGE: &str = "Usage:
+    aoc-2019 run <day> [--part 1|2] [--input <path>|-] [--input-dir <dir>]
+    aoc-2019 run --all [--input-dir <dir>]
+    aoc-2019 list
+    aoc-2019 bench [<day>] [--runs <n>] [--output <path.csv|path.json>]
+                   [--baseline <path>] [--threshold <percent>] [--input-dir <dir>]
+    aoc-2019 verify [--answers <path>] [--input-dir <dir>]
+    aoc-2019 image <day> <path.pbm|.pgm|.ppm|.png> [--scale <n>] [--palette <path>]
+                   [--input <path>|-] [--input-dir <dir>]
+    aoc-2019 animate <day> <path.gif|dir> [--scale <n>] [--delay <centiseconds>]
+                     [--input <path>|-] [--input-dir <dir>]
+    aoc-2019 station [--at <x,y>] [--targets <n>] [--heat] [--input <path>|-]
+                     [--input-dir <dir>]
+    aoc-2019 hull [--start black|white] [--svg <path>] [--script <path>|--ant <steps>]
+                  [--input <path>|-] [--input-dir <dir>]
+    aoc-2019 cfg <day>
+    aoc-2019 smc <day>
+    aoc-2019 diff
+
+Inputs are read from --input (- for stdin), then <dir>/day<N>/input with <dir>
+from --input-dir or $AOC_INPUT_DIR, then the input bundled with the repo.
+
+station lists all --targets but only marks the first 35 on the map, 1 to 9 then a to z.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run {
+        day: u8,
+        // Both parts when None
+        part: Option<u8>,
+        input: Option<String>,
+        input_dir: Option<String>,
+    },
+    RunAll {
+        input_dir: Option<String>,
+    },
+    List,
+    Bench {
+        // Every day when None
+        day: Option<u8>,
+        runs: usize,
+        output: Option<String>,
+        baseline: Option<String>,
+        // Percent a median may grow over the baseline before it's a regression
+        threshold: f64,
+        input_dir: Option<String>,
+    },
+    Verify {
+        // The bundled answers.toml when None
+        answers: Option<String>,
+        input_dir: Option<String>,
+    },
+    // Day 8 or 11's picture, format picked by the path's extension
+    Image {
+        day: u8,
+        path: String,
+        scale: usize,
+        // Day 8 only, digit colors to composite the layers with
+        palette: Option<String>,
+        input: Option<String>,
+        input_dir: Option<String>,
+    },
+    // Day 8's image one layer at a time, as a GIF or a directory of PNG frames
+    Animate {
+        day: u8,
+        path: String,
+        scale: usize,
+        // Hundredths of a second per GIF frame
+        delay: u16,
+        input: Option<String>,
+        input_dir: Option<String>,
+    },
+    // Day 10's map with the monitoring station and what its laser hits first
+    Station {
+        // The asteroid that sees the most when None
+        at: Option<(i32, i32)>,
+        targets: usize,
+        // Show how many asteroids each asteroid sees instead
+        heat: bool,
+        input: Option<String>,
+        input_dir: Option<String>,
+    },
+    // Day 11's painted hull and how the robot got there
+    Hull {
+        // The starting panel is black when false, like part 1
+        start_white: bool,
+        // Where to save the robot's path over the hull
+        svg: Option<String>,
+        // Colors and turns to follow instead of running the Intcode program
+        script: Option<String>,
+        // Steps of Langton's ant to run instead of the Intcode program
+        ant: Option<usize>,
+        input: Option<String>,
+        input_dir: Option<String>,
+    },
+    // Intcode tools
+    Cfg(u8),
+    Smc(u8),
+    Diff,
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    value.parse().map_err(|_| format!("Invalid day {}", value))
+}
+
+fn parse_run(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut all = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--part" => match args.next().map(|v| v.as_str()) {
+                Some("1") => part = Some(1),
+                Some("2") => part = Some(2),
+                Some(v) => return Err(format!("Invalid part {}", v)),
+                None => return Err("Missing part".to_string()),
+            },
+            "--input" => input = Some(args.next().ok_or("Missing input path")?.clone()),
+            "--input-dir" => {
+                input_dir = Some(args.next().ok_or("Missing input directory")?.clone())
+            }
+            _ if day.is_none() && !arg.starts_with("--") => day = Some(parse_day(arg)?),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    match (all, day) {
+        (true, None) if part.is_none() && input.is_none() => Ok(Command::RunAll { input_dir }),
+        (true, _) => Err("--all can't be combined with a day, --part or --input".to_string()),
+        (false, Some(day)) => Ok(Command::Run {
+            day,
+            part,
+            input,
+            input_dir,
+        }),
+        (false, None) => Err("Missing day".to_string()),
+    }
+}
+
+fn parse_bench(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut runs = 10;
+    let mut output = None;
+    let mut baseline = None;
+    let mut threshold = 10.;
+    let mut input_dir = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--runs" => {
+                let v = value()?;
+                runs = match v.parse() {
+                    Ok(runs) if runs > 0 => runs,
+                    _ => return Err(format!("Invalid runs {}", v)),
+                };
+            }
+            "--output" => output = Some(value()?.clone()),
+            "--baseline" => baseline = Some(value()?.clone()),
+            "--input-dir" => input_dir = Some(value()?.clone()),
+            "--threshold" => {
+                let v = value()?;
+                threshold = v.parse().map_err(|_| format!("Invalid threshold {}", v))?;
+            }
+            _ if day.is_none() && !arg.starts_with("--") => day = Some(parse_day(arg)?),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    Ok(Command::Bench {
+        day,
+        runs,
+        output,
+        baseline,
+        threshold,
+        input_dir,
+    })
+}
+
+fn parse_verify(args: &[String]) -> Result<Command, String> {
+    let mut answers = None;
+    let mut input_dir = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--answers" => answers = Some(value()?.clone()),
+            "--input-dir" => input_dir = Some(value()?.clone()),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    Ok(Command::Verify { answers, input_dir })
+}
+
+// `image` and `animate` take the same arguments, plus a palette for `image` and a frame
+// delay for `animate`
+fn parse_picture(args: &[String], animate: bool) -> Result<Command, String> {
+    let mut positional = vec![];
+    let mut scale = 1;
+    let mut delay = 50;
+    let mut palette = None;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--scale" => {
+                let v = value()?;
+                scale = match v.parse() {
+                    Ok(scale) if scale > 0 => scale,
+                    _ => return Err(format!("Invalid scale {}", v)),
+                };
+            }
+            "--delay" if animate => {
+                let v = value()?;
+                delay = v.parse().map_err(|_| format!("Invalid delay {}", v))?;
+            }
+            "--palette" if !animate => palette = Some(value()?.clone()),
+            "--input" => input = Some(value()?.clone()),
+            "--input-dir" => input_dir = Some(value()?.clone()),
+            _ if !arg.starts_with("--") => positional.push(arg),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+
+    match positional.as_slice() {
+        [day, path] if animate => Ok(Command::Animate {
+            day: parse_day(day)?,
+            path: path.to_string(),
+            scale,
+            delay,
+            input,
+            input_dir,
+        }),
+        [day, path] => Ok(Command::Image {
+            day: parse_day(day)?,
+            path: path.to_string(),
+            scale,
+            palette,
+            input,
+            input_dir,
+        }),
+        _ => Err("Expected a day and an output path".to_string()),
+    }
+}
+
+fn parse_station(args: &[String]) -> Result<Command, String> {
+    let mut at = None;
+    let mut targets = 10;
+    let mut heat = false;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--at" => {
+                let v = value()?;
+                let xy = v
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)));
+                at = Some(xy.ok_or(format!("Invalid position {}", v))?);
+            }
+            "--targets" => {
+                let v = value()?;
+                targets = v.parse().map_err(|_| format!("Invalid targets {}", v))?;
+            }
+            "--heat" => heat = true,
+            "--input" => input = Some(value()?.clone()),
+            "--input-dir" => input_dir = Some(value()?.clone()),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    Ok(Command::Station {
+        at,
+        targets,
+        heat,
+        input,
+        input_dir,
+    })
+}
+
+fn parse_hull(args: &[String]) -> Result<Command, String> {
+    let mut start_white = true;
+    let mut svg = None;
+    let mut script = None;
+    let mut ant = None;
+    let mut input = None;
+    let mut input_dir = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--start" => {
+                start_white = match value()?.as_str() {
+                    "black" => false,
+                    "white" => true,
+                    v => return Err(format!("Invalid color {}", v)),
+                }
+            }
+            "--svg" => svg = Some(value()?.clone()),
+            "--script" => script = Some(value()?.clone()),
+            "--ant" => {
+                let v = value()?;
+                ant = Some(v.parse().map_err(|_| format!("Invalid steps {}", v))?);
+            }
+            "--input" => input = Some(value()?.clone()),
+            "--input-dir" => input_dir = Some(value()?.clone()),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    if script.is_some() && ant.is_some() {
+        return Err("--script can't be combined with --ant".to_string());
+    }
+    Ok(Command::Hull {
+        start_white,
+        svg,
+        script,
+        ant,
+        input,
+        input_dir,
+    })
+}
+
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    match args.split_first() {
+        Some((command, rest)) => match (command.as_str(), rest) {
+            ("run", rest) => parse_run(rest),
+            ("list", []) => Ok(Command::List),
+            ("bench", rest) => parse_bench(rest),
+            ("verify", rest) => parse_verify(rest),
+            ("image", rest) => parse_picture(rest, false),
+            ("animate", rest) => parse_picture(rest, true),
+            ("station", rest) => parse_station(rest),
+            ("hull", rest) => parse_hull(rest),
+            ("cfg", [day]) => parse_day(day).map(Command::Cfg),
+            ("smc", [day]) => parse_day(day).map(Command::Smc),
+            ("diff", []) => Ok(Command::Diff),
+            _ => Err(format!("Invalid command {}", args.join(" "))),
+        },
+        None => Err("Missing command".to_string()),
+    }
+}
+
+#[test]
+fn test_parse() {
+    let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+    assert_eq!(
+        parse(&args("run 2")),
+        Ok(Command::Run {
+            day: 2,
+            part: None,
+            input: None,
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("run --part 2 10 --input in.txt")),
+        Ok(Command::Run {
+            day: 10,
+            part: Some(2),
+            input: Some("in.txt".to_string()),
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("run --all --input-dir inputs")),
+        Ok(Command::RunAll {
+            input_dir: Some("inputs".to_string())
+        })
+    );
+    assert_eq!(parse(&args("list")), Ok(Command::List));
+    assert_eq!(
+        parse(&args(
+            "bench 4 --runs 3 --output now.csv --baseline before.csv"
+        )),
+        Ok(Command::Bench {
+            day: Some(4),
+            runs: 3,
+            output: Some("now.csv".to_string()),
+            baseline: Some("before.csv".to_string()),
+            threshold: 10.,
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("verify --answers mine.toml")),
+        Ok(Command::Verify {
+            answers: Some("mine.toml".to_string()),
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("image 8 id.ppm --scale 10 --palette warm.toml")),
+        Ok(Command::Image {
+            day: 8,
+            path: "id.ppm".to_string(),
+            scale: 10,
+            palette: Some("warm.toml".to_string()),
+            input: None,
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("animate 8 frames --delay 20 --input -")),
+        Ok(Command::Animate {
+            day: 8,
+            path: "frames".to_string(),
+            scale: 1,
+            delay: 20,
+            input: Some("-".to_string()),
+            input_dir: None
+        })
+    );
+    assert_eq!(parse(&args("cfg 13")), Ok(Command::Cfg(13)));
+    assert_eq!(parse(&args("diff")), Ok(Command::Diff));
+    assert_eq!(
+        parse(&args("hull --start black --svg path.svg")),
+        Ok(Command::Hull {
+            start_white: false,
+            svg: Some("path.svg".to_string()),
+            script: None,
+            ant: None,
+            input: None,
+            input_dir: None
+        })
+    );
+    assert_eq!(
+        parse(&args("station --at 11,13 --targets 20")),
+        Ok(Command::Station {
+            at: Some((11, 13)),
+            targets: 20,
+            heat: false,
+            input: None,
+            input_dir: None
+        })
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+    assert!(parse(&args("")).is_err());
+    assert!(parse(&args("21")).is_err());
+    assert!(parse(&args("run")).is_err());
+    assert!(parse(&args("run x")).is_err());
+    assert!(parse(&args("run 2 --part 3")).is_err());
+    assert!(parse(&args("run 2 3")).is_err());
+    assert!(parse(&args("run --all 2")).is_err());
+    assert!(parse(&args("run 2 --input")).is_err());
+    assert!(parse(&args("list 2")).is_err());
+    assert!(parse(&args("bench --runs 0")).is_err());
+    assert!(parse(&args("bench --threshold")).is_err());
+    assert!(parse(&args("verify 2")).is_err());
+    assert!(parse(&args("image 8")).is_err());
+    assert!(parse(&args("image 8 id.png --scale 0")).is_err());
+    assert!(parse(&args("image 8 id.png --delay 10")).is_err());
+    assert!(parse(&args("animate 8 id.gif --delay -1")).is_err());
+    assert!(parse(&args("station --at 3")).is_err());
+    assert!(parse(&args("hull --start red")).is_err());
+    assert!(parse(&args("hull --ant 100 --script moves.txt")).is_err());
+}
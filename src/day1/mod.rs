@@ -1,7 +1,10 @@
-use std::fs;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+const INPUT: &str = include_str!("./input");
 
 // Part 1
-fn _fuel(mass: usize) -> usize {
+fn simple_fuel(mass: usize) -> usize {
     mass / 3 - 2
 }
 
@@ -13,21 +16,53 @@ fn fuel(mass: usize) -> usize {
     }
 }
 
-pub fn answer() -> usize {
-    fs::read_to_string("src/day1/input")
-        .unwrap()
+// One mass per line, blank lines are skipped
+fn parse_masses(input: &str) -> Result<Vec<usize>, ParseError> {
+    input
         .lines()
-        .filter_map(|line| line.parse::<usize>().ok())
-        .map(fuel)
-        .sum()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse()
+                .map_err(|_| ParseError::at(input, line, format!("invalid mass {:?}", line)))
+        })
+        .collect()
+}
+
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_masses(input)?.into_iter().map(simple_fuel).sum())
+}
+
+pub fn part_2(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_masses(input)?.into_iter().map(fuel).sum())
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
 fn calc_fuel_part_1() {
-    assert_eq!(_fuel(12), 2);
-    assert_eq!(_fuel(14), 2);
-    assert_eq!(_fuel(1969), 654);
-    assert_eq!(_fuel(100_756), 33583);
+    assert_eq!(simple_fuel(12), 2);
+    assert_eq!(simple_fuel(14), 2);
+    assert_eq!(simple_fuel(1969), 654);
+    assert_eq!(simple_fuel(100_756), 33583);
 }
 
 #[test]
@@ -37,3 +72,15 @@ fn calc_fuel_part_2() {
     assert_eq!(fuel(1969), 966);
     assert_eq!(fuel(100_756), 50346);
 }
+
+#[test]
+fn test_parse_masses() {
+    let e = parse_masses("12\nxx\n14").unwrap_err();
+    assert_eq!(
+        (e.line, e.column, e.message.as_str()),
+        (2, 1, "invalid mass \"xx\"")
+    );
+}
+
+fixture_tests!(Day1);
+answer_tests!(Day1);
@@ -1,35 +1,42 @@
-use std::collections::HashMap;
-use std::f32;
+use crate::grid::{Grid, Point};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+use std::thread;
 
 const INPUT: &str = include_str!("./input");
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
+// Binary GCD: shifts and subtractions instead of the divisions Euclid's needs, which
+// matters when it runs for every pair of asteroids
 fn gcd(a: i32, b: i32) -> i32 {
-    let a = a.abs();
-    let b = b.abs();
-    if b == 0 {
-        return a;
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    if a == 0 || b == 0 {
+        return (a | b) as i32;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    while b != 0 {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
     }
-    gcd(b, a % b)
+    (a << shift) as i32
 }
 
-fn angle(p1: Position, p2: Position) -> (i32, i32) {
-    // Top-left coordinates
-    let y = p1.y - p2.y;
-    let x = p2.x - p1.x;
-    let gcd = gcd(y, x);
-
-    (x / gcd, y / gcd)
+// Direction from p1 to p2 with the common factor taken out
+fn angle(p1: Point, p2: Point) -> Point {
+    let d = p2 - p1;
+    d / gcd(d.x, d.y)
 }
 
-fn gen_angles(points: &[Position], p: Position) -> (Position, Vec<(i32, i32)>) {
+#[cfg(test)]
+fn gen_angles(points: &[Point], p: Point) -> (Point, Vec<Point>) {
     (
-        Position { x: p.x, y: p.y },
+        Point { x: p.x, y: p.y },
         points
             .iter()
             .filter(|v| **v != p)
@@ -38,56 +45,68 @@ fn gen_angles(points: &[Position], p: Position) -> (Position, Vec<(i32, i32)>) {
     )
 }
 
-fn gen_clockwise_angles(points: &[Position], p: Position) -> HashMap<(i32, i32), Vec<Position>> {
+fn gen_clockwise_angles(points: &[Point], p: Point) -> HashMap<Point, Vec<Point>> {
     let mut ret = HashMap::new();
     for target in points.iter().filter(|v| **v != p) {
-        let entry = ret.entry(angle(p, *target)).or_insert_with(|| vec![]);
+        let entry = ret.entry(angle(p, *target)).or_insert_with(Vec::new);
         entry.push(*target);
     }
     ret
 }
 
-fn vaporize(input: &str, nth: u32) -> Option<Position> {
-    let points = parse_position(input);
-    match max_count_detects(&points) {
-        Some((central, _)) => {
-            // println!("Central {:?}", central);
-            let mut clockwise_map = gen_clockwise_angles(&points, central);
-            let mut angles: Vec<_> = clockwise_map
-                .iter_mut()
-                .map(|(angle, points)| {
-                    points.sort_by_key(|Position { y, .. }| y.abs());
-                    (angle, points)
-                })
-                .collect();
-            let mut pick = 0;
-            angles.sort_by(|((x1, y1), _), ((x2, y2), _)| {
-                clockwise_degree(*x1 as f32, *y1 as f32)
-                    .partial_cmp(&clockwise_degree(*x2 as f32, *y2 as f32))
-                    .unwrap()
-            });
-            // println!("sorted angles: {:?}", angles);
-            loop {
-                for (_, points_in_line) in angles.iter_mut() {
-                    if let Some(v) = points_in_line.pop() {
-                        pick += 1;
-                        if pick == nth {
-                            return Some(v);
-                        }
-                    }
-                }
-            }
+// Every asteroid but the station, in the order the laser hits them: clockwise from up,
+// the nearest on each line of sight each turn
+pub fn vaporization_order(points: &[Point], station: Point) -> Vec<Point> {
+    let mut lines = gen_clockwise_angles(points, station)
+        .into_iter()
+        .collect::<Vec<_>>();
+    lines.sort_by(|(a, _), (b, _)| clockwise(*a, *b));
+    for (_, points_in_line) in lines.iter_mut() {
+        // Farthest first, so `pop` takes the nearest
+        points_in_line.sort_by_key(|p| {
+            let d = *p - station;
+            Reverse(d.x.abs() + d.y.abs())
+        });
+    }
+
+    let mut order = vec![];
+    while lines
+        .iter()
+        .any(|(_, points_in_line)| !points_in_line.is_empty())
+    {
+        for (_, points_in_line) in lines.iter_mut() {
+            order.extend(points_in_line.pop());
         }
-        _ => panic!("No max central point"),
     }
+    order
+}
+
+fn vaporize(points: &[Point], nth: usize) -> Result<Point, String> {
+    let (central, _) = max_count_detects(points).ok_or("no asteroids")?;
+    let order = vaporization_order(points, central);
+    order
+        .get(nth - 1)
+        .copied()
+        .ok_or_else(|| format!("only {} asteroids to vaporize", order.len()))
+}
+
+pub fn part_2(input: &str) -> Result<i32, ParseError> {
+    vaporize(&parse_position(input)?, 200)
+        .map(|Point { x, y }| x * 100 + y)
+        .map_err(|e| ParseError::at_end(input, e))
 }
 
-pub fn part_2() -> i32 {
-    vaporize(INPUT, 200)
-        .map(|Position { x, y }| x * 100 + y)
-        .unwrap()
+// Orders directions clockwise from straight up, exactly: by the half of the plane they
+// point into (right of up, or down and left of it), then by which side of each other
+// they're on. Directions that are multiples of each other are equal.
+fn clockwise(a: Point, b: Point) -> Ordering {
+    let half = |p: Point| !(p.x > 0 || (p.x == 0 && p.y < 0));
+    let cross = a.x as i64 * b.y as i64 - a.y as i64 * b.x as i64;
+    half(a).cmp(&half(b)).then(0.cmp(&cross))
 }
 
+// The float angle `clockwise` replaced, with y counting up
+#[cfg(test)]
 fn clockwise_degree(x: f32, y: f32) -> f32 {
     let angle = y.atan2(x);
     let mut degree = angle.to_degrees();
@@ -101,42 +120,188 @@ fn clockwise_degree(x: f32, y: f32) -> f32 {
     degree % 360.
 }
 
-fn count_detects(points: &[Position], p: Position) -> (Position, usize) {
+// The first way of counting, kept to check `count_detects` and `max_count_detects` against
+#[cfg(test)]
+fn count_detects_sorted(points: &[Point], p: Point) -> (Point, usize) {
     let (central, mut angles) = gen_angles(points, p);
     angles.sort();
-    // println!("For p = {:?}, angles sorted: {:?}", p, angles);
     angles.dedup();
     (central, angles.len())
 }
 
-fn parse_position(input: &str) -> Vec<Position> {
-    let mut ret = vec![];
+#[cfg(test)]
+fn max_count_detects_sorted(points: &[Point]) -> Option<(Point, usize)> {
+    points
+        .iter()
+        .map(|p| count_detects_sorted(points, *p))
+        .max_by_key(|v| v.1)
+}
+
+// Multiply and rotate, much cheaper than the default SipHash for small integer keys that
+// don't come from an attacker
+#[derive(Default)]
+struct DirectionHasher(u64);
+
+impl Hasher for DirectionHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u32(*byte as u32);
+        }
+    }
+
+    fn write_u32(&mut self, v: u32) {
+        self.0 = (self.0.rotate_left(5) ^ v as u64).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    fn write_i32(&mut self, v: i32) {
+        self.write_u32(v as u32);
+    }
+}
+
+type Directions = HashSet<Point, BuildHasherDefault<DirectionHasher>>;
 
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if let '#' = c {
-                ret.push(Position {
-                    x: x as i32,
-                    y: y as i32,
+// Asteroids behind each other share a reduced direction, so count the distinct ones.
+// `seen` is only there to reuse its allocation.
+fn count_detects(points: &[Point], p: Point, seen: &mut Directions) -> usize {
+    seen.clear();
+    seen.extend(points.iter().filter(|v| **v != p).map(|v| angle(p, *v)));
+    seen.len()
+}
+
+// `count_detects` for every asteroid, in the same order, split across threads
+fn count_all_detects(points: &[Point]) -> Vec<usize> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = points.len().div_ceil(threads).max(1);
+    thread::scope(|s| {
+        let handles = points
+            .chunks(chunk)
+            .map(|chunk| {
+                s.spawn(move || {
+                    let mut seen = Directions::default();
+                    chunk
+                        .iter()
+                        .map(|p| count_detects(points, *p, &mut seen))
+                        .collect::<Vec<_>>()
                 })
-            }
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+// True where there's an asteroid
+pub fn parse_map(input: &str) -> Result<Grid<bool>, ParseError> {
+    for line in input.lines() {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| *c != '#' && *c != '.') {
+            return Err(ParseError::at(
+                input,
+                &line[i..],
+                format!("invalid cell {:?}", c),
+            ));
         }
     }
+    Grid::parse(input, |c| c == '#')
+}
 
-    ret
+pub fn asteroids(map: &Grid<bool>) -> Vec<Point> {
+    map.iter()
+        .filter(|(_, asteroid)| **asteroid)
+        .map(|(p, _)| p)
+        .collect()
+}
+
+fn parse_position(input: &str) -> Result<Vec<Point>, ParseError> {
+    Ok(asteroids(&parse_map(input)?))
+}
+
+// How many other asteroids each asteroid can see, None where there isn't one
+pub fn visibility(map: &Grid<bool>) -> Grid<Option<usize>> {
+    let points = asteroids(map);
+    let mut counts = map.map(|_| None);
+    for (p, count) in points.iter().zip(count_all_detects(&points)) {
+        counts[*p] = Some(count);
+    }
+    counts
+}
+
+// Visibility scaled from 0 for the fewest to 9 for the most, `.` for empty space
+pub fn render_heat_map(counts: &Grid<Option<usize>>) -> String {
+    let seen = counts.iter().filter_map(|(_, c)| *c);
+    let (min, max) = seen.fold((usize::MAX, 0), |(min, max), c| (min.min(c), max.max(c)));
+    counts
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|count| match count {
+                    Some(c) => {
+                        let level = (c - min) * 9 / (max - min).max(1);
+                        std::char::from_digit(level as u32, 10).unwrap()
+                    }
+                    None => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// The map with the station as `X` and the first 35 targets numbered 1 to 9, then a to z.
+// Any later targets stay `#`, there are no more single characters to number them with.
+pub fn render_targets(map: &Grid<bool>, station: Point, targets: &[Point]) -> String {
+    let mut cells = map.map(|asteroid| if *asteroid { '#' } else { '.' });
+    cells[station] = 'X';
+    for (i, target) in targets.iter().take(35).enumerate() {
+        cells[*target] = std::char::from_digit(i as u32 + 1, 36).unwrap();
+    }
+    cells
+        .rows()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-fn max_count_detects(points: &[Position]) -> Option<(Position, usize)> {
+// The asteroid that sees the most others, and how many it sees. On a tie it's the last
+// one in `points`.
+pub fn max_count_detects(points: &[Point]) -> Option<(Point, usize)> {
     points
         .iter()
-        .map(|p| count_detects(&points, *p))
+        .copied()
+        .zip(count_all_detects(points))
         .max_by_key(|v| v.1)
 }
 
-pub fn part_1() -> usize {
-    let points = parse_position(INPUT);
-    let (_, max) = max_count_detects(&points).unwrap();
-    max
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    let points = parse_position(input)?;
+    let (_, max) =
+        max_count_detects(&points).ok_or_else(|| ParseError::at_end(input, "no asteroids"))?;
+    Ok(max)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
@@ -146,25 +311,31 @@ fn test_vaporize() {
 ##...#...#.#####.
 ..#.....#...###..
 ..#.#.....#....##";
-    assert_eq!(vaporize(input, 1), Some(Position { x: 8, y: 1 }));
-    assert_eq!(vaporize(input, 2), Some(Position { x: 9, y: 0 }));
+    let points = parse_position(input).unwrap();
+    assert_eq!(vaporize(&points, 1), Ok(Point { x: 8, y: 1 }));
+    assert_eq!(vaporize(&points, 2), Ok(Point { x: 9, y: 0 }));
+    assert_eq!(
+        vaporize(&points, 200),
+        Err("only 36 asteroids to vaporize".to_string())
+    );
+    assert_eq!(vaporize(&[], 1), Err("no asteroids".to_string()));
 }
 
 #[test]
 fn test_clockwise_angle() {
     // top-left coordinates
-    let clockwise_angle_between = |start: &Position, end: &Position| -> f32 {
+    let clockwise_angle_between = |start: &Point, end: &Point| -> f32 {
         clockwise_degree((end.x - start.x) as f32, (start.y - end.y) as f32)
     };
 
-    let north = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: 0, y: -1 });
-    let north_east = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: 1, y: -1 });
-    let east = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: 1, y: 0 });
-    let south_east = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: 1, y: 1 });
-    let south = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: 0, y: 1 });
-    let south_west = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: -1, y: 1 });
-    let west = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: -1, y: 0 });
-    let north_west = clockwise_angle_between(&Position { x: 0, y: 0 }, &Position { x: -1, y: -1 });
+    let north = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: 0, y: -1 });
+    let north_east = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: 1, y: -1 });
+    let east = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: 1, y: 0 });
+    let south_east = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: 1, y: 1 });
+    let south = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: 0, y: 1 });
+    let south_west = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: -1, y: 1 });
+    let west = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: -1, y: 0 });
+    let north_west = clockwise_angle_between(&Point { x: 0, y: 0 }, &Point { x: -1, y: -1 });
     // println!(
     //     "north = {:?}, north_east = {:?}, east = {:?}, south_east = {:?}, south = {:?}, south_west = {:?}, west = {:?}, north_west = {:?}",
     //     north, north_east, east, south_east, south, south_west, west, north_west
@@ -179,73 +350,212 @@ fn test_clockwise_angle() {
     assert!((north_west - 315.).abs() <= f32::EPSILON);
     // println!(
     //     "test clock degree {}",
-    //     clockwise_degree(0., 3.) // clockwise_angle_between(&Position { x: 8, y: 3 }, &Position { x: 8, y: 0 })
+    //     clockwise_degree(0., 3.) // clockwise_angle_between(&Point { x: 8, y: 3 }, &Point { x: 8, y: 0 })
     // );
 }
 
-#[test]
-fn test_gcd() {
-    assert_eq!(gcd(18, 48), 6);
-    assert_eq!(gcd(0, 0), 0);
-    assert_eq!(gcd(1, 0), 1);
-    assert_eq!(gcd(0, 1), 1);
-    assert_eq!(gcd(18, -48), 6);
-    assert_eq!(gcd(11, 13), 1);
-    assert_eq!(gcd(-11, 13), 1);
+// Roughly `density` of the cells are asteroids
+#[cfg(test)]
+fn synthetic_field(width: i32, height: i32, density: f64, mut seed: u64) -> Vec<Point> {
+    let mut points = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            if ((seed >> 33) as f64 / (1u64 << 31) as f64) < density {
+                points.push(Point::new(x, y));
+            }
+        }
+    }
+    points
 }
 
 #[test]
 fn test_max_detect_count() {
-    let mut input = r".#..#
-.....
-#####
-....#
-...##";
+    // The fixtures only check the counts, this is where the stations are
+    let examples = [
+        (include_str!("../../fixtures/day10/small.txt"), (3, 4), 8),
+        (include_str!("../../fixtures/day10/medium1.txt"), (5, 8), 33),
+        (include_str!("../../fixtures/day10/medium2.txt"), (1, 2), 35),
+        (include_str!("../../fixtures/day10/medium3.txt"), (6, 3), 41),
+        (
+            include_str!("../../fixtures/day10/large.txt"),
+            (11, 13),
+            210,
+        ),
+    ];
+    for (fixture, (x, y), count) in &examples {
+        let input = crate::fixtures::parse(fixture).unwrap().input;
+        assert_eq!(
+            max_count_detects(&parse_position(&input).unwrap()),
+            Some((Point::new(*x, *y), *count))
+        );
+    }
+}
 
+#[test]
+fn test_vaporization_order() {
+    // The puzzle's example of a whole laser run
+    let large = crate::fixtures::parse(include_str!("../../fixtures/day10/large.txt")).unwrap();
+    let points = parse_position(&large.input).unwrap();
+    let order = vaporization_order(&points, Point::new(11, 13));
+    assert_eq!(order.len(), 299);
+    let nth = |n: usize| (order[n - 1].x, order[n - 1].y);
+    assert_eq!(nth(1), (11, 12));
+    assert_eq!(nth(10), (12, 8));
+    assert_eq!(nth(50), (16, 9));
+    assert_eq!(nth(100), (10, 16));
+    assert_eq!(nth(201), (10, 9));
+    assert_eq!(nth(299), (11, 1));
+    // The station and the first 35 targets get a label, the rest stay asteroids
+    let map = parse_map(&large.input).unwrap();
+    let rendered = render_targets(&map, Point::new(11, 13), &order);
     assert_eq!(
-        max_count_detects(&parse_position(input)),
-        Some((Position { x: 3, y: 4 }, 8))
+        rendered
+            .matches(|c| c != '#' && c != '.' && c != '\n')
+            .count(),
+        36
     );
+    assert_eq!(rendered.matches('#').count(), 299 - 35);
 
-    input = r"......#.#.
-#..#.#....
-..#######.
-.#.#.###..
-.#..#.....
-..#....#.#
-#..#....#.
-.##.#..###
-##...#..#.
-.#....####";
+    // Nearest first on each line of sight, also below the station
+    let map = parse_map("#\n.\n#\n#\n#").unwrap();
+    let order = vaporization_order(&asteroids(&map), Point::new(0, 2));
+    assert_eq!(
+        order,
+        vec![Point::new(0, 0), Point::new(0, 3), Point::new(0, 4)]
+    );
+    assert_eq!(
+        render_targets(&map, Point::new(0, 2), &order),
+        "1\n.\nX\n2\n3"
+    );
+}
 
+#[test]
+fn test_visibility() {
+    let map = parse_map(".#..#\n.....\n#####\n....#\n...##").unwrap();
+    let counts = visibility(&map);
+    assert_eq!(counts[Point::new(3, 4)], Some(8));
+    assert_eq!(counts[Point::new(0, 0)], None);
+    // Counts from 5 to 8 spread over 0 to 9
     assert_eq!(
-        max_count_detects(&parse_position(input)),
-        Some((Position { x: 5, y: 8 }, 33))
+        render_heat_map(&counts),
+        ".6..6\n.....\n36660\n....6\n...96"
     );
+}
 
-    input = r".#..##.###...#######
-##.############..##.
-.#.######.########.#
-.###.#######.####.#.
-#####.##.#.##.###.##
-..#####..#.#########
-####################
-#.####....###.#.#.##
-##.#################
-#####.##.###..####..
-..######..##.#######
-####.##.####...##..#
-.#####..#.######.###
-##...#.##########...
-#.##########.#######
-.####.#.###.###.#.##
-....##.##.###..#####
-.#.#.###########.###
-#.#.#.#####.####.###
-###.##.####.##.#..##";
+#[test]
+fn test_clockwise() {
+    let compass = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ]
+    .iter()
+    .map(|(x, y)| Point::new(*x, *y))
+    .collect::<Vec<_>>();
+    for (i, a) in compass.iter().enumerate() {
+        for (j, b) in compass.iter().enumerate() {
+            assert_eq!(clockwise(*a, *b), i.cmp(&j), "{:?} {:?}", a, b);
+        }
+    }
+    assert_eq!(
+        clockwise(Point::new(2, -4), Point::new(1, -2)),
+        Ordering::Equal
+    );
 
+    // Every direction from the middle of a big field, in the order f64 angles give
+    let field = synthetic_field(400, 400, 0.3, 10);
+    let station = Point::new(200, 200);
+    let mut directions = field
+        .iter()
+        .filter(|p| **p != station)
+        .map(|p| angle(station, *p))
+        .collect::<Vec<_>>();
+    directions.sort_by(|a, b| clockwise(*a, *b));
+    directions.dedup();
+    let degrees = |p: &Point| {
+        let degree = (-p.y as f64).atan2(p.x as f64).to_degrees();
+        (450. - degree) % 360.
+    };
+    assert!(directions
+        .windows(2)
+        .all(|w| degrees(&w[0]) < degrees(&w[1])));
+
+    // Far enough out, f32 can't tell neighboring directions apart
+    let (a, b) = (Point::new(1, -3001), Point::new(1, -3000));
+    assert_eq!(clockwise(a, b), Ordering::Less);
+    assert_eq!(
+        clockwise_degree(a.x as f32, -a.y as f32),
+        clockwise_degree(b.x as f32, -b.y as f32)
+    );
+}
+
+#[test]
+fn test_count_detects() {
+    for seed in 0..4 {
+        let field = synthetic_field(30, 20, 0.15 + seed as f64 * 0.15, seed);
+        let mut seen = Directions::default();
+        for p in &field {
+            assert_eq!(
+                count_detects(&field, *p, &mut seen),
+                count_detects_sorted(&field, *p).1
+            );
+        }
+        assert_eq!(max_count_detects(&field), max_count_detects_sorted(&field));
+    }
+    assert_eq!(max_count_detects(&[]), None);
+}
+
+// cargo test --release bench_count_detects -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_count_detects() {
+    use std::time::Instant;
+
+    for seed in 0..3 {
+        let field = synthetic_field(200, 200, 0.25, seed);
+        println!("{} asteroids", field.len());
+
+        let start = Instant::now();
+        let expected = max_count_detects_sorted(&field);
+        println!("Sorted: {:?}", start.elapsed());
+
+        let start = Instant::now();
+        assert_eq!(max_count_detects(&field), expected);
+        println!("Hashed and threaded: {:?}", start.elapsed());
+    }
+}
+
+#[test]
+fn test_gcd() {
+    assert_eq!(gcd(18, 48), 6);
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(gcd(1, 0), 1);
+    assert_eq!(gcd(0, 1), 1);
+    assert_eq!(gcd(18, -48), 6);
+    assert_eq!(gcd(11, 13), 1);
+    assert_eq!(gcd(-11, 13), 1);
+}
+
+#[test]
+fn test_parse_errors() {
+    let e = parse_map(".#.\n.x#").unwrap_err();
     assert_eq!(
-        max_count_detects(&parse_position(input)),
-        Some((Position { x: 11, y: 13 }, 210))
+        (e.line, e.column, e.message.as_str()),
+        (2, 2, "invalid cell 'x'")
     );
+    // Another day's input, or nothing at all
+    assert_eq!(part_1("1234").unwrap_err().message, "invalid cell '1'");
+    assert_eq!(part_1("").unwrap_err().message, "no asteroids");
+    assert_eq!(part_2("...").unwrap_err().message, "no asteroids");
 }
+
+fixture_tests!(Day10);
+answer_tests!(Day10);
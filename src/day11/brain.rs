@@ -0,0 +1,174 @@
+use super::Color;
+use crate::grid::Turn;
+use crate::intcode::{intcode_parser, Action, Machine};
+use crate::parse::ParseError;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+// Decides what the robot does. Each step it's shown the panel under the robot, paints it,
+// then turns. Returning None from either stops the robot.
+pub trait Brain {
+    fn paint(&mut self, panel: Color) -> Option<Color>;
+    fn turn(&mut self) -> Option<Turn>;
+
+    // Why the robot stopped, when it wasn't meant to
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+// The puzzle's robot: a program reading panel colors and writing a color then a turn.
+// It stops at the first output that isn't 0 or 1.
+pub struct Intcode {
+    machine: Machine,
+    error: Option<String>,
+}
+
+impl Intcode {
+    pub fn new(program: &str) -> Result<Self, ParseError> {
+        let program = intcode_parser(program)?;
+        Ok(Intcode {
+            machine: Machine::with_capacity(&program, 2000),
+            error: None,
+        })
+    }
+
+    fn output<T: TryFrom<isize, Error = isize>>(&mut self, what: &str) -> Option<T> {
+        match self.machine.run() {
+            Action::Output(v) => T::try_from(v)
+                .map_err(|v| self.error = Some(format!("invalid {} {}", what, v)))
+                .ok(),
+            Action::Halt => None,
+        }
+    }
+}
+
+impl Brain for Intcode {
+    fn paint(&mut self, panel: Color) -> Option<Color> {
+        self.machine.push_input(panel as isize);
+        self.output("color")
+    }
+
+    fn turn(&mut self) -> Option<Turn> {
+        self.output("turn")
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+// Fixed colors and turns whatever the panels are, then stops
+pub struct Script {
+    steps: VecDeque<(Color, Turn)>,
+    turn: Option<Turn>,
+}
+
+impl Script {
+    pub fn new(steps: Vec<(Color, Turn)>) -> Self {
+        Script {
+            steps: steps.into(),
+            turn: None,
+        }
+    }
+
+    // The numbers an Intcode brain would output: a color (0 black, 1 white) then a turn
+    // (0 left, 1 right), separated by commas or whitespace
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut values = vec![];
+        for value in input.split(|c: char| c == ',' || c.is_whitespace()) {
+            match value {
+                "" => continue,
+                "0" | "1" => values.push(value == "1"),
+                _ => {
+                    return Err(ParseError::at(
+                        input,
+                        value,
+                        format!("expected 0 or 1, got {:?}", value),
+                    ))
+                }
+            }
+        }
+        if values.len() % 2 == 1 {
+            return Err(ParseError::at_end(
+                input.trim_end(),
+                "a color without a turn",
+            ));
+        }
+        let steps = values
+            .chunks(2)
+            .map(|pair| {
+                let color = if pair[0] { Color::White } else { Color::Black };
+                let turn = if pair[1] { Turn::Right } else { Turn::Left };
+                (color, turn)
+            })
+            .collect();
+        Ok(Script::new(steps))
+    }
+}
+
+impl Brain for Script {
+    fn paint(&mut self, _: Color) -> Option<Color> {
+        let (color, turn) = self.steps.pop_front()?;
+        self.turn = Some(turn);
+        Some(color)
+    }
+
+    fn turn(&mut self) -> Option<Turn> {
+        self.turn.take()
+    }
+}
+
+// Right and paint white on black, left and paint black on white, for a number of steps
+pub struct LangtonsAnt {
+    steps: usize,
+    turn: Option<Turn>,
+}
+
+impl LangtonsAnt {
+    pub fn new(steps: usize) -> Self {
+        LangtonsAnt { steps, turn: None }
+    }
+}
+
+impl Brain for LangtonsAnt {
+    fn paint(&mut self, panel: Color) -> Option<Color> {
+        self.steps = self.steps.checked_sub(1)?;
+        let (turn, color) = match panel {
+            Color::Black => (Turn::Right, Color::White),
+            Color::White => (Turn::Left, Color::Black),
+        };
+        self.turn = Some(turn);
+        Some(color)
+    }
+
+    fn turn(&mut self) -> Option<Turn> {
+        self.turn.take()
+    }
+}
+
+#[test]
+fn test_script_parse() {
+    let mut script = Script::parse("1,0\n0 1\n").unwrap();
+    assert_eq!(script.paint(Color::Black), Some(Color::White));
+    assert_eq!(script.turn(), Some(Turn::Left));
+    assert_eq!(script.paint(Color::White), Some(Color::Black));
+    assert_eq!(script.turn(), Some(Turn::Right));
+    assert_eq!(script.paint(Color::Black), None);
+
+    let error = |input| Script::parse(input).map(|_| ()).unwrap_err().to_string();
+    assert_eq!(
+        error("1,0,2,1"),
+        "line 1, column 5: expected 0 or 1, got \"2\""
+    );
+    assert_eq!(error("1,0,1"), "line 1, column 6: a color without a turn");
+}
+
+#[test]
+fn test_intcode_bad_output() {
+    // Paints white, then outputs 2 for the turn
+    let mut brain = Intcode::new("104,1,104,2,99").unwrap();
+    assert_eq!(brain.paint(Color::Black), Some(Color::White));
+    assert_eq!(brain.turn(), None);
+    assert_eq!(brain.error(), Some("invalid turn 2".to_string()));
+}
@@ -1,131 +1,340 @@
-use crate::intcode::{intcode_parser, Action, Machine};
-use std::collections::HashMap;
+mod brain;
+
+use crate::grid::{BoundingBox, Direction, Grid, Point, SparseGrid, Turn};
+use crate::image::{self, Rgb};
+use crate::ocr;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+pub use brain::{Brain, Intcode, LangtonsAnt, Script};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use Color::*;
 
 const INPUT: &str = include_str!("./input");
 
-type Coord = (i32, i32);
+struct Hull {
+    panels: SparseGrid<Color>,
+    paint_counts: SparseGrid<usize>,
+    steps: usize,
+    replay: Vec<Move>,
+    locate: (Point, Direction),
+}
 
-struct Grid {
-    panels: HashMap<Coord, Color>,
-    locate: (Coord, Direction),
-    machine: Machine,
+// What the robot left behind
+pub struct Painting {
+    // Cropped to the panels the robot painted, plus the one it started on
+    pub panels: Grid<Color>,
+    // How often each panel was painted, panels never painted are missing
+    pub paint_counts: SparseGrid<usize>,
+    // Moves the robot made
+    pub steps: usize,
+    // Every paint in order
+    pub replay: Vec<Move>,
+    // Where the top left panel is on the hull, the robot starts at (0, 0)
+    pub origin: Point,
+    // Where the robot stopped and which way it faced, past the last paint if it moved on
+    pub end: (Point, Direction),
 }
 
-impl Grid {
-    fn new(program: &str, start_color: Color) -> Self {
-        let mut panels = HashMap::new();
-        let start = (0, 0);
-        let program = intcode_parser(program);
-        let mut machine = Machine::with_capacity(&program, 2000);
+// One paint: where the robot stood, which way it faced and the color it painted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    pub coord: Point,
+    pub direction: Direction,
+    pub color: Color,
+}
 
-        machine.push_input(start_color as isize);
-        panels.insert(start, Black);
+impl Hull {
+    fn new(start_color: Color) -> Self {
+        let mut panels = SparseGrid::new();
+        let start = Point::new(0, 0);
+        panels.insert(start, start_color);
 
-        Grid {
+        Hull {
             panels,
-            machine,
+            paint_counts: SparseGrid::new(),
+            steps: 0,
+            replay: vec![],
             locate: (start, Direction::Up),
         }
     }
 
-    fn run(&mut self) {
+    fn run(&mut self, brain: &mut dyn Brain) {
         loop {
-            // First output color
-            match self.machine.run() {
-                Action::Output(v) => {
-                    self.panels.insert(self.locate.0, Color::from(v));
-                }
-                Action::Halt => break,
-            }
-            // Second output direction
-            match self.machine.run() {
-                Action::Output(v) => {
-                    let (coord, direction) = &self.locate;
-                    let (x, y) = *coord;
-                    self.locate = match (Turn::from(v), direction) {
-                        (Turn::Left, Direction::Up) | (Turn::Right, Direction::Down) => {
-                            ((x - 1, y), Direction::Left)
-                        }
-                        (Turn::Left, Direction::Down) | (Turn::Right, Direction::Up) => {
-                            ((x + 1, y), Direction::Right)
-                        }
-                        (Turn::Left, Direction::Left) | (Turn::Right, Direction::Right) => {
-                            ((x, y - 1), Direction::Down)
-                        }
-                        (Turn::Left, Direction::Right) | (Turn::Right, Direction::Left) => {
-                            ((x, y + 1), Direction::Up)
-                        }
-                    };
-                    let next_panel_color = self.panels.get(&self.locate.0).unwrap_or(&Black);
-                    self.machine.push_input(*next_panel_color as isize)
+            let (coord, direction) = self.locate;
+            let panel = *self.panels.get(coord).unwrap_or(&Black);
+            let color = match brain.paint(panel) {
+                Some(color) => color,
+                None => break,
+            };
+            self.panels.insert(coord, color);
+            self.replay.push(Move {
+                coord,
+                direction,
+                color,
+            });
+            let count = self.paint_counts.get(coord).map_or(0, |n| *n);
+            self.paint_counts.insert(coord, count + 1);
+            match brain.turn() {
+                Some(turn) => {
+                    let direction = direction.turn(turn);
+                    self.locate = (coord + direction.offset(), direction);
+                    self.steps += 1;
                 }
-                Action::Halt => break,
+                None => break,
             }
         }
     }
 }
 
-#[derive(Copy, Clone)]
-enum Color {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Color {
     White = 1,
     Black = 0,
 }
 
-impl From<isize> for Color {
-    fn from(v: isize) -> Color {
+impl TryFrom<isize> for Color {
+    type Error = isize;
+
+    fn try_from(v: isize) -> Result<Self, isize> {
         match v {
-            1 => White,
-            0 => Black,
-            _ => unimplemented!(),
+            1 => Ok(White),
+            0 => Ok(Black),
+            _ => Err(v),
         }
     }
 }
 
-enum Turn {
-    Left = 0,
-    Right = 1,
-}
+impl TryFrom<isize> for Turn {
+    type Error = isize;
 
-impl From<isize> for Turn {
-    fn from(v: isize) -> Turn {
+    fn try_from(v: isize) -> Result<Self, isize> {
         match v {
-            1 => Turn::Right,
-            0 => Turn::Left,
-            _ => unimplemented!(),
+            1 => Ok(Turn::Right),
+            0 => Ok(Turn::Left),
+            _ => Err(v),
         }
     }
 }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-pub fn part_1() -> usize {
-    let mut grid = Grid::new(INPUT.trim(), Black);
-    grid.run();
-    grid.panels.keys().len()
-}
-
-pub fn part_2() {
-    let mut grid = Grid::new(INPUT.trim(), White);
-    grid.run();
-    let coords = grid.panels.keys();
-    let min_x = coords.clone().min_by_key(|k| k.0).unwrap().0;
-    let max_x = coords.clone().max_by_key(|k| k.0).unwrap().0;
-    let min_y = coords.clone().min_by_key(|k| k.1).unwrap().1;
-    let max_y = coords.clone().max_by_key(|k| k.1).unwrap().1;
-    for y in (min_y..=max_y).rev() {
-        for x in min_x..=max_x {
-            let color = match grid.panels.get(&(x, y)) {
-                Some(White) => "⬜️",
-                _ => "⬛️",
-            };
-            print!("{}", color);
+impl Painting {
+    // Panels painted at least once
+    pub fn painted(&self) -> usize {
+        self.paint_counts.len()
+    }
+
+    // Panels painted more than once
+    pub fn repainted(&self) -> usize {
+        self.paint_counts.values().filter(|n| **n > 1).count()
+    }
+
+    pub fn render(&self) -> String {
+        self.panels
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|color| match color {
+                        White => "⬜️",
+                        Black => "⬛️",
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Counts and the number of panels painted that many times, fewest paints first
+    pub fn paint_histogram(&self) -> Vec<(usize, usize)> {
+        let mut histogram = BTreeMap::new();
+        for n in self.paint_counts.values() {
+            *histogram.entry(*n).or_insert(0) += 1;
+        }
+        histogram.into_iter().collect()
+    }
+
+    // The final hull with the robot's path drawn over it, from a green dot where it started
+    // to a blue one where it stopped
+    pub fn svg(&self, cell: usize) -> String {
+        let mut path = self.replay.iter().map(|m| m.coord).collect::<Vec<_>>();
+        path.push(self.end.0);
+        path.dedup();
+        // The robot can stop on a panel it never painted, outside `panels`
+        let corner = self.origin
+            + Point::new(
+                self.panels.width() as i32 - 1,
+                self.panels.height() as i32 - 1,
+            );
+        let bounds = BoundingBox::of(vec![self.origin, corner, self.end.0]).unwrap();
+
+        let center = |p: Point| {
+            let p = p - bounds.min;
+            let half = cell as f64 / 2.0;
+            (
+                p.x as f64 * cell as f64 + half,
+                p.y as f64 * cell as f64 + half,
+            )
+        };
+        let (width, height) = (bounds.width() * cell, bounds.height() * cell);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            width, height
+        );
+        svg += "<rect width=\"100%\" height=\"100%\" fill=\"black\"/>\n";
+        for (p, color) in self.panels.iter() {
+            if *color == White {
+                let p = p + self.origin - bounds.min;
+                svg += &format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"white\"/>\n",
+                    p.x as usize * cell,
+                    p.y as usize * cell,
+                    cell
+                );
+            }
+        }
+
+        let points = path
+            .iter()
+            .map(|p| {
+                let (x, y) = center(*p);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>();
+        svg += &format!(
+            "<polyline fill=\"none\" stroke=\"#e04040\" stroke-opacity=\"0.6\" stroke-width=\"{}\" points=\"{}\"/>\n",
+            cell as f64 / 5.0,
+            points.join(" ")
+        );
+        let ends = [path[0], path[path.len() - 1]];
+        for (p, fill) in ends.iter().zip(&["#40c040", "#4080ff"]) {
+            let (x, y) = center(*p);
+            svg += &format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                x,
+                y,
+                cell as f64 / 3.0,
+                fill
+            );
         }
-        println!();
+        svg + "</svg>\n"
+    }
+
+    pub fn text(&self) -> Result<String, ocr::UnknownGlyphs> {
+        ocr::recognize(&self.panels.map(|color| *color == White))
     }
 }
+
+// Runs the Intcode robot from a panel of `start` color until its program halts
+pub fn paint(input: &str, start: Color) -> Result<Painting, ParseError> {
+    Ok(paint_with(&mut Intcode::new(input.trim())?, start))
+}
+
+pub fn paint_with(brain: &mut dyn Brain, start: Color) -> Painting {
+    let mut hull = Hull::new(start);
+    hull.run(brain);
+    Painting {
+        panels: hull.panels.to_dense(Black).unwrap(),
+        origin: hull.panels.bounding_box().unwrap().min,
+        end: hull.locate,
+        paint_counts: hull.paint_counts,
+        steps: hull.steps,
+        replay: hull.replay,
+    }
+}
+
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    Ok(paint(input, Black)?.painted())
+}
+
+// The registration identifier, or the painted panels when it can't be read
+pub fn part_2(input: &str) -> Result<String, ParseError> {
+    let painting = paint(input, White)?;
+    Ok(painting
+        .text()
+        .unwrap_or_else(|e| format!("{}\n{}", painting.render(), e)))
+}
+
+pub fn image(input: &str) -> Result<Grid<Rgb>, ParseError> {
+    Ok(paint(input, White)?.panels.map(|color| match color {
+        White => image::WHITE,
+        Black => image::BLACK,
+    }))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
+}
+
+#[test]
+fn test_paint() {
+    let painting = paint(INPUT, White).unwrap();
+    assert_eq!(painting.text(), Ok("BCKFPCRA".to_string()));
+    assert_eq!((painting.painted(), painting.steps), (249, 249));
+    assert_eq!(painting.paint_histogram(), vec![(1, 249)]);
+
+    let painting = paint(INPUT, Black).unwrap();
+    let histogram = painting.paint_histogram();
+    assert_eq!(histogram.iter().map(|(_, n)| n).sum::<usize>(), 2255);
+    assert_eq!(painting.repainted(), 2255 - histogram[0].1);
+    assert_eq!(painting.steps, 10601);
+    let paints = histogram
+        .iter()
+        .map(|(paints, n)| paints * n)
+        .sum::<usize>();
+    assert_eq!(painting.replay.len(), paints);
+    assert_eq!(
+        (painting.replay[0].coord, painting.replay[0].direction),
+        (Point::new(0, 0), Direction::Up)
+    );
+
+    let svg = painting.svg(10);
+    let white = painting.panels.iter().filter(|(_, c)| **c == White).count();
+    assert_eq!(svg.matches("fill=\"white\"").count(), white);
+    assert_eq!(svg.matches("<circle").count(), 2);
+}
+
+#[test]
+fn test_scripted_robot() {
+    // The moves from the puzzle's example
+    let mut script = Script::parse("1,0, 0,0, 1,0, 1,0, 0,1, 1,0, 1,0").unwrap();
+    let painting = paint_with(&mut script, Black);
+    assert_eq!((painting.painted(), painting.steps), (6, 7));
+    assert_eq!(painting.render(), "⬛️⬛️⬜️\n⬛️⬛️⬜️\n⬜️⬜️⬛️");
+    assert_eq!(painting.origin, Point::new(-1, -1));
+    // The last move goes on past the last paint, up from (1, -1) and left to (0, -1)
+    assert_eq!(painting.end, (Point::new(0, -1), Direction::Left));
+    let svg = painting.svg(10);
+    assert!(svg.contains("points=\"15,15 5,15 5,25 15,25 15,15 25,15 25,5 15,5\""));
+    assert!(svg.contains("<circle cx=\"15\" cy=\"5\" r=\"3.3333333333333335\" fill=\"#4080ff\"/>"));
+}
+
+#[test]
+fn test_langtons_ant() {
+    // Four rights make a white square around the start
+    let painting = paint_with(&mut LangtonsAnt::new(4), Black);
+    assert_eq!(painting.render(), "⬜️⬜️\n⬜️⬜️");
+    assert_eq!(painting.steps, 4);
+
+    // Then it turns left off the white start, painting it black again
+    let painting = paint_with(&mut LangtonsAnt::new(5), Black);
+    assert_eq!(painting.replay[4].color, Black);
+    assert_eq!(painting.replay[4].direction, Direction::Up);
+    assert_eq!(painting.paint_counts.get(Point::new(0, 0)), Some(&2));
+}
+
+answer_tests!(Day11);
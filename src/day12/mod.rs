@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use std::cmp::Ordering;
 
 const INPUT: &str = include_str!("./input");
@@ -39,7 +41,7 @@ fn apply_velocity(p: &mut [Position], v: &[Velocity]) {
 }
 
 fn energy_after_steps(moons: &[Position], steps: usize) -> i32 {
-    let mut velocity: Vec<Velocity> = std::iter::repeat((0, 0, 0)).take(moons.len()).collect();
+    let mut velocity: Vec<Velocity> = std::iter::repeat_n((0, 0, 0), moons.len()).collect();
     let mut positions: Vec<Position> = moons.to_vec();
     for _ in 0..steps {
         apply_velocity(&mut velocity, &gen_velocity(&positions));
@@ -52,24 +54,53 @@ fn energy_after_steps(moons: &[Position], steps: usize) -> i32 {
         .sum()
 }
 
-fn parse_input(input: &str) -> Vec<Position> {
+// Lines like `<x=-1, y=0, z=2>`
+fn parse_input(input: &str) -> Result<Vec<Position>, ParseError> {
+    let error = |part: &str, message: String| ParseError::at(input, part, message);
     input
         .lines()
         .map(|line| {
-            let position = line
-                .chars()
-                .filter(|c| *c == '-' || *c == ',' || c.is_digit(10))
-                .collect::<String>()
-                .split(',')
-                .map(|s| s.parse::<i32>().unwrap())
-                .collect::<Vec<_>>();
-            (position[0], position[1], position[2])
+            let fields = line
+                .strip_prefix('<')
+                .and_then(|l| l.strip_suffix('>'))
+                .map(|l| l.split(',').map(str::trim_start).collect::<Vec<_>>())
+                .filter(|fields| fields.len() == 3)
+                .ok_or_else(|| {
+                    error(line, format!("expected <x=.., y=.., z=..>, got {:?}", line))
+                })?;
+            let mut position = [0; 3];
+            for ((field, name), v) in fields.iter().zip(&["x", "y", "z"]).zip(&mut position) {
+                let value = field
+                    .strip_prefix(name)
+                    .and_then(|f| f.strip_prefix('='))
+                    .ok_or_else(|| error(field, format!("expected {}=..", name)))?;
+                *v = value
+                    .parse()
+                    .map_err(|_| error(value, format!("invalid number {:?}", value)))?;
+            }
+            Ok((position[0], position[1], position[2]))
         })
         .collect()
 }
 
-pub fn part_1() -> i32 {
-    energy_after_steps(&parse_input(INPUT), 1000)
+pub fn part_1(input: &str) -> Result<i32, ParseError> {
+    Ok(energy_after_steps(&parse_input(input)?, 1000))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
 }
 
 #[test]
@@ -91,6 +122,15 @@ fn test_energy_after_steps() {
 fn test_parse_input() {
     assert_eq!(
         parse_input(INPUT),
-        vec![(1, 4, 4), (-4, -1, 19), (-15, -14, 12), (-17, 1, 10)]
+        Ok(vec![(1, 4, 4), (-4, -1, 19), (-15, -14, 12), (-17, 1, 10)])
     );
+    let error = |input| {
+        let e = parse_input(input).unwrap_err();
+        (e.line, e.column)
+    };
+    assert_eq!(error("<x=1, y=2, z=3>\n<x=1, y=2>"), (2, 1));
+    assert_eq!(error("<x=1, q=2, z=3>"), (1, 7));
+    assert_eq!(error("<x=1, y=2, z=3a>"), (1, 14));
 }
+
+answer_tests!(Day12);
@@ -1,20 +1,51 @@
-use crate::intcode::{intcode_parser, Action, Machine};
-use std::collections::HashSet;
+use crate::grid::{Point, SparseGrid};
+use crate::intcode::{intcode_parser, Action, ControlFlowGraph, Machine, SelfModifyingLog};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 
 const INPUT: &str = include_str!("./input");
 
-pub fn part_1() -> usize {
-    let program = intcode_parser(INPUT.trim());
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    let program = intcode_parser(input.trim())?;
     let mut machine = Machine::with_capacity(&program, 3000);
-    let mut blocks = HashSet::new();
+    let mut screen = SparseGrid::new();
 
     while let (Action::Output(x), Action::Output(y), Action::Output(tile)) =
         (machine.run(), machine.run(), machine.run())
     {
-        if tile == 2 {
-            blocks.insert((x, y));
-        }
+        screen.insert(Point::new(x as i32, y as i32), tile);
     }
 
-    blocks.len()
+    // Block tiles
+    Ok(screen.values().filter(|tile| **tile == 2).count())
 }
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+}
+
+pub fn self_modifying() -> SelfModifyingLog {
+    let program = intcode_parser(INPUT.trim()).unwrap();
+    let mut machine = Machine::with_capacity(&program, 3000);
+    machine.track_self_modifying();
+    while let Action::Output(_) = machine.run() {}
+    machine.into_self_modifying().unwrap()
+}
+
+pub fn cfg() -> String {
+    ControlFlowGraph::new(&intcode_parser(INPUT.trim()).unwrap()).to_dot()
+}
+
+answer_tests!(Day13);
@@ -1,4 +1,10 @@
-use std::fs;
+mod symbolic;
+
+use crate::intcode::{diff_runs, intcode_parser, Machine, MachineDiff};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+const INPUT: &str = include_str!("./input");
 
 fn run(codes: &mut [usize]) {
     for i in (0..codes.len()).step_by(4) {
@@ -20,36 +26,87 @@ fn run_with(codes: &mut [usize], noun: usize, verb: usize) -> usize {
     codes[0]
 }
 
-fn parse_input() -> Vec<usize> {
-    fs::read_to_string("src/day2/input")
-        .unwrap()
+// Like `intcode_parser`, but day 2 programs only ever hold addresses and unsigned values
+fn parse_input(input: &str) -> Result<Vec<usize>, ParseError> {
+    let input = input.trim();
+    let codes = input
         .split(',')
-        .filter_map(|v| v.parse().ok())
-        .collect()
+        .map(|v| {
+            v.parse()
+                .map_err(|_| ParseError::at(input, v, format!("invalid intcode {:?}", v)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // The noun and verb go in 1 and 2
+    if codes.len() < 3 {
+        return Err(ParseError::at_end(
+            input,
+            format!("expected at least 3 intcodes, got {}", codes.len()),
+        ));
+    }
+    Ok(codes)
 }
 
-pub fn part_1() -> usize {
-    let mut codes = parse_input();
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    let mut codes = parse_input(input)?;
 
-    run_with(&mut codes, 12, 2)
+    Ok(run_with(&mut codes, 12, 2))
 }
 
-pub fn part_2() -> usize {
-    let codes = parse_input();
-    let mut noun = 0;
-    let mut verb = 0;
-
-    for i in 0..=99 {
-        for j in 0..=99 {
-            if run_with(&mut codes.clone(), i, j) == 19_690_720 {
-                noun = i;
-                verb = j;
-                break;
-            }
-        }
+fn brute_force(codes: &[usize], target: usize) -> Option<(usize, usize)> {
+    (0..=99)
+        .flat_map(|noun| (0..=99).map(move |verb| (noun, verb)))
+        .find(|(noun, verb)| run_with(&mut codes.to_vec(), *noun, *verb) == target)
+}
+
+pub fn part_2(input: &str) -> Result<usize, ParseError> {
+    let codes = parse_input(input)?;
+    // Solve directly when memory[0] is linear in noun and verb
+    let (noun, verb) = symbolic::solve(&codes, 19_690_720)
+        .or_else(|| brute_force(&codes, 19_690_720))
+        .ok_or_else(|| ParseError::at_end(input.trim(), "no noun and verb gives 19690720"))?;
+
+    Ok(100 * noun + verb)
+}
+
+fn machine_with(program: &[isize], noun: isize, verb: isize) -> Machine {
+    let mut program = program.to_vec();
+    program[1] = noun;
+    program[2] = verb;
+    Machine::new(&program)
+}
+
+// Which cells react to noun and verb: (0, 0) diffed against (1, 0) and against (0, 1)
+pub fn noun_verb_diffs(input: &str) -> Result<(MachineDiff, MachineDiff), ParseError> {
+    let program = intcode_parser(input.trim())?;
+    let diff = |noun, verb| {
+        diff_runs(
+            &mut machine_with(&program, 0, 0),
+            &mut machine_with(&program, noun, verb),
+        )
+        .pop()
+        .unwrap()
+    };
+    Ok((diff(1, 0), diff(0, 1)))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    fn day(&self) -> u8 {
+        2
     }
 
-    100 * noun + verb
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
@@ -73,10 +130,50 @@ fn test_run() {
 
 #[test]
 fn test_part_1() {
-    assert_eq!(part_1(), 4_138_658);
+    assert_eq!(part_1(INPUT), Ok(4_138_658));
 }
 
 #[test]
 fn test_part_2() {
-    assert_eq!(part_2(), 7264);
+    assert_eq!(part_2(INPUT), Ok(7264));
+}
+
+#[test]
+fn test_symbolic_matches_brute_force() {
+    let codes = parse_input(INPUT).unwrap();
+    assert_eq!(symbolic::solve(&codes, 19_690_720), Some((72, 64)));
+    assert_eq!(brute_force(&codes, 19_690_720), Some((72, 64)));
 }
+
+#[test]
+fn test_wrong_program() {
+    assert_eq!(
+        part_1("99\n").unwrap_err().message,
+        "expected at least 3 intcodes, got 1"
+    );
+    assert_eq!(
+        part_2("99,0,0").unwrap_err().message,
+        "no noun and verb gives 19690720"
+    );
+}
+
+#[test]
+fn test_noun_verb_diffs() {
+    // memory[0] of (0, 0) and its change when noun or verb goes up by one
+    let output = |diff: &MachineDiff| {
+        let (_, l, r) = diff
+            .memory
+            .iter()
+            .find(|(address, ..)| *address == 0)
+            .unwrap();
+        (*l, r - l)
+    };
+    let (noun, verb) = noun_verb_diffs(INPUT).unwrap();
+    let (base, noun_delta) = output(&noun);
+    let (_, verb_delta) = output(&verb);
+    assert_eq!(verb_delta, 1);
+    // Linear in noun and verb, which is why part 2's answer is 100 * 72 + 64
+    assert_eq!(base + noun_delta * 72 + verb_delta * 64, 19_690_720);
+}
+
+answer_tests!(Day2);
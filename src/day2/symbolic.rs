@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::ops::{Add, Mul};
+
+// Polynomial in noun and verb: (noun exponent, verb exponent) => coefficient
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial(BTreeMap<(u32, u32), i64>);
+
+impl Polynomial {
+    fn term(exponents: (u32, u32), coefficient: i64) -> Self {
+        let mut terms = BTreeMap::new();
+        if coefficient != 0 {
+            terms.insert(exponents, coefficient);
+        }
+        Polynomial(terms)
+    }
+
+    pub fn constant(value: i64) -> Self {
+        Polynomial::term((0, 0), value)
+    }
+
+    pub fn noun() -> Self {
+        Polynomial::term((1, 0), 1)
+    }
+
+    pub fn verb() -> Self {
+        Polynomial::term((0, 1), 1)
+    }
+
+    fn as_constant(&self) -> Option<i64> {
+        match self.0.iter().next() {
+            None => Some(0),
+            Some(((0, 0), c)) if self.0.len() == 1 => Some(*c),
+            _ => None,
+        }
+    }
+
+    // (noun, verb, constant) coefficients, if there is no term of higher degree
+    pub fn as_linear(&self) -> Option<(i64, i64, i64)> {
+        let mut linear = (0, 0, 0);
+        for (exponents, c) in &self.0 {
+            match exponents {
+                (1, 0) => linear.0 = *c,
+                (0, 1) => linear.1 = *c,
+                (0, 0) => linear.2 = *c,
+                _ => return None,
+            }
+        }
+        Some(linear)
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &Polynomial) -> Polynomial {
+        let mut terms = self.0.clone();
+        for (exponents, c) in &other.0 {
+            *terms.entry(*exponents).or_insert(0) += c;
+        }
+        terms.retain(|_, c| *c != 0);
+        Polynomial(terms)
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: &Polynomial) -> Polynomial {
+        let mut terms = BTreeMap::new();
+        for ((n1, v1), c1) in &self.0 {
+            for ((n2, v2), c2) in &other.0 {
+                *terms.entry((n1 + n2, v1 + v2)).or_insert(0) += c1 * c2;
+            }
+        }
+        terms.retain(|_, c| *c != 0);
+        Polynomial(terms)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Known(Polynomial),
+    // Read through an address depending on noun or verb
+    Unknown,
+}
+
+fn address(value: &Value) -> Option<usize> {
+    match value {
+        Value::Known(p) => p.as_constant().map(|v| v as usize),
+        Value::Unknown => None,
+    }
+}
+
+// memory[0] after running with memory[1] and memory[2] as noun and verb.
+// Values read through symbolic addresses are fine as long as they never reach
+// memory[0], an opcode or an address; None otherwise.
+pub fn execute(codes: &[usize]) -> Option<Polynomial> {
+    let mut memory: Vec<Value> = codes
+        .iter()
+        .map(|v| Value::Known(Polynomial::constant(*v as i64)))
+        .collect();
+    memory[1] = Value::Known(Polynomial::noun());
+    memory[2] = Value::Known(Polynomial::verb());
+
+    let read = |memory: &[Value], pointer: &Value| match address(pointer) {
+        Some(address) => memory.get(address).cloned(),
+        None => Some(Value::Unknown),
+    };
+
+    for i in (0..memory.len()).step_by(4) {
+        let op = address(&memory[i])?;
+        if op == 99 {
+            break;
+        }
+        let lhs = read(&memory, memory.get(i + 1)?)?;
+        let rhs = read(&memory, memory.get(i + 2)?)?;
+        let output = address(memory.get(i + 3)?)?;
+        let value = match (op, lhs, rhs) {
+            (1, Value::Known(lhs), Value::Known(rhs)) => Value::Known(&lhs + &rhs),
+            (2, Value::Known(lhs), Value::Known(rhs)) => Value::Known(&lhs * &rhs),
+            (1, _, _) | (2, _, _) => Value::Unknown,
+            _ => return None,
+        };
+        *memory.get_mut(output)? = value;
+    }
+
+    match &memory[0] {
+        Value::Known(p) => Some(p.clone()),
+        Value::Unknown => None,
+    }
+}
+
+// Noun and verb in 0..=99 producing `target`, if memory[0] turns out linear in them.
+pub fn solve(codes: &[usize], target: usize) -> Option<(usize, usize)> {
+    let (a, b, c) = execute(codes)?.as_linear()?;
+    let target = target as i64;
+    (0..=99).find_map(|noun| {
+        let rest = target - c - a * noun;
+        let verb = match b {
+            0 if rest == 0 => 0,
+            0 => return None,
+            b if rest % b == 0 => rest / b,
+            _ => return None,
+        };
+        if (0..=99).contains(&verb) {
+            Some((noun as usize, verb as usize))
+        } else {
+            None
+        }
+    })
+}
+
+#[test]
+fn test_execute_linear() {
+    // 0: [0] = garbage; 4: [0] = noun + verb; 8: [0] *= 3
+    let codes = [1, 0, 0, 0, 1, 1, 2, 0, 2, 0, 13, 0, 99, 3];
+    let p = execute(&codes).unwrap();
+    assert_eq!(p.as_linear(), Some((3, 3, 0)));
+}
+
+#[test]
+fn test_execute_non_linear() {
+    // 0: [0] = garbage; 4: [0] = noun * verb
+    let codes = [2, 0, 0, 0, 2, 1, 2, 0, 99];
+    let p = execute(&codes).unwrap();
+    assert_eq!(p, &Polynomial::noun() * &Polynomial::verb());
+    assert_eq!(p.as_linear(), None);
+}
+
+#[test]
+fn test_execute_unknown_result() {
+    // memory[0] = memory[noun] + memory[verb]
+    let codes = [1, 0, 0, 0, 99];
+    assert_eq!(execute(&codes), None);
+}
+
+#[test]
+fn test_solve() {
+    // 0: [15] = garbage; 4: [0] = noun * 100; 8: [0] += verb
+    let codes = [1, 0, 0, 15, 2, 1, 16, 0, 1, 0, 2, 0, 99, 0, 0, 0, 100];
+    assert_eq!(solve(&codes, 1234), Some((12, 34)));
+    assert_eq!(solve(&codes, 100_000), None);
+}
@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 use std::ops::AddAssign;
 use Direction::*;
@@ -23,19 +25,45 @@ impl AddAssign<&Direction> for (i32, i32) {
     }
 }
 
-fn parse_instruction(instruction: &str) -> (Direction, i32) {
-    let (direction, distance) = instruction.split_at(1);
-    match (direction, distance.parse()) {
-        ("R", Ok(v)) => (Right, v),
-        ("L", Ok(v)) => (Left, v),
-        ("U", Ok(v)) => (Up, v),
-        ("D", Ok(v)) => (Down, v),
-        _ => panic!("Invalid instruction {}", instruction),
-    }
+type Wire = Vec<(Direction, i32)>;
+
+// `input` is only there to locate errors in
+fn parse_instruction(input: &str, instruction: &str) -> Result<(Direction, i32), ParseError> {
+    let mut chars = instruction.chars();
+    let direction = match chars.next() {
+        Some('R') => Right,
+        Some('L') => Left,
+        Some('U') => Up,
+        Some('D') => Down,
+        _ => {
+            return Err(ParseError::at(
+                input,
+                instruction,
+                format!("invalid instruction {:?}", instruction),
+            ))
+        }
+    };
+    let distance = chars.as_str();
+    distance
+        .parse()
+        .map(|v| (direction, v))
+        .map_err(|_| ParseError::at(input, distance, format!("invalid distance {:?}", distance)))
+}
+
+fn parse_wires(input: &str) -> Result<(Wire, Wire), ParseError> {
+    let mut lines = input.lines();
+    let mut wire = || -> Result<Wire, ParseError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::at_end(input, "expected two wires"))?;
+        line.split(',')
+            .map(|instruction| parse_instruction(input, instruction))
+            .collect()
+    };
+    Ok((wire()?, wire()?))
 }
 
-fn parse_wire_coords(input: &str) -> HashMap<(i32, i32), i32> {
-    let instructions = input.split(',').map(parse_instruction);
+fn parse_wire_coords(instructions: Wire) -> HashMap<(i32, i32), i32> {
     let mut coord = (0, 0);
     let mut steps = 0;
     let mut coord_map = HashMap::new();
@@ -52,36 +80,71 @@ fn parse_wire_coords(input: &str) -> HashMap<(i32, i32), i32> {
     coord_map
 }
 
-pub fn part_1() -> Option<i32> {
-    let mut lines = INPUT.lines();
-    let wire1 = parse_wire_coords(lines.next().expect("Failed to read puzzle input."));
-    let wire2 = parse_wire_coords(lines.next().expect("Failed to read puzzle input."));
+pub fn part_1(input: &str) -> Result<Option<i32>, ParseError> {
+    let (wire1, wire2) = parse_wires(input)?;
+    let wire1 = parse_wire_coords(wire1);
+    let wire2 = parse_wire_coords(wire2);
 
-    wire2
+    Ok(wire2
         .keys()
         .filter(|coord| wire1.contains_key(coord))
         .map(|(x, y)| x.abs() + y.abs())
-        .min()
+        .min())
 }
 
-pub fn part_2() -> Option<i32> {
-    let mut lines = INPUT.lines();
-    let wire1 = parse_wire_coords(lines.next().expect("Failed to read puzzle input."));
-    let wire2 = parse_wire_coords(lines.next().expect("Failed to read puzzle input."));
+pub fn part_2(input: &str) -> Result<Option<i32>, ParseError> {
+    let (wire1, wire2) = parse_wires(input)?;
+    let wire1 = parse_wire_coords(wire1);
+    let wire2 = parse_wire_coords(wire2);
 
-    wire2
+    Ok(wire2
         .keys()
         .filter(|coord| wire1.contains_key(coord))
         .map(|k| wire1[k] + wire2[k])
-        .min()
+        .min())
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
 fn test_part_1() {
-    assert_eq!(part_1(), Some(232));
+    assert_eq!(part_1(INPUT), Ok(Some(232)));
 }
 
 #[test]
 fn test_part_2() {
-    assert_eq!(part_2(), Some(6084));
+    assert_eq!(part_2(INPUT), Ok(Some(6084)));
 }
+
+#[test]
+fn test_parse_errors() {
+    let error = |input| {
+        let e = parse_wires(input).map(|_| ()).unwrap_err();
+        (e.line, e.column)
+    };
+    assert_eq!(error("R8,U5\nU7,X6"), (2, 4));
+    assert_eq!(error("R8,U5\nU7,R6x"), (2, 5));
+    assert_eq!(error("R8,,U5\nU7"), (1, 4));
+    assert_eq!(error("R8,U5\n"), (2, 1));
+}
+
+fixture_tests!(Day3);
+answer_tests!(Day3);
@@ -1,5 +1,7 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
-use std::ops::Range;
+use std::ops::RangeInclusive;
 
 fn validate_rule_1(pass: u32) -> bool {
     let digit_chars: Vec<char> = pass.to_string().chars().collect();
@@ -29,23 +31,72 @@ fn validate_rule_2(pass: u32) -> bool {
     pairs.values().any(|count| *count == 2)
 }
 
-const INPUT: Range<u32> = (359_282..820_401);
+const INPUT: &str = "359282-820401";
 
-pub fn part_1() -> usize {
-    INPUT.filter(|v| validate_rule_1(*v)).count()
+// `low-high`, both ends included
+fn parse_range(input: &str) -> Result<RangeInclusive<u32>, ParseError> {
+    let range = input.trim();
+    let (low, high) = range.split_once('-').ok_or_else(|| {
+        ParseError::at(input, range, format!("expected low-high, got {:?}", range))
+    })?;
+    let bound = |v: &str| {
+        v.parse::<u32>()
+            .map_err(|_| ParseError::at(input, v, format!("invalid bound {:?}", v)))
+    };
+    Ok(bound(low)?..=bound(high)?)
 }
 
-pub fn part_2() -> usize {
-    INPUT.filter(|v| validate_rule_2(*v)).count()
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_range(input)?.filter(|v| validate_rule_1(*v)).count())
+}
+
+pub fn part_2(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_range(input)?.filter(|v| validate_rule_2(*v)).count())
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
 fn test_valid_rule_2() {
-    assert_eq!(validate_rule_2(112_233), true);
-    assert_eq!(validate_rule_2(111_122), true);
-    assert_eq!(validate_rule_2(123_444), false);
-    assert_eq!(validate_rule_2(111_123), false);
-    assert_eq!(validate_rule_2(144_446), false);
-    assert_eq!(validate_rule_2(455_888), true);
-    assert_eq!(validate_rule_2(344_445_667), true);
+    assert!(validate_rule_2(112_233));
+    assert!(validate_rule_2(111_122));
+    assert!(!validate_rule_2(123_444));
+    assert!(!validate_rule_2(111_123));
+    assert!(!validate_rule_2(144_446));
+    assert!(validate_rule_2(455_888));
+    assert!(validate_rule_2(344_445_667));
 }
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(parse_range("359282-820401\n"), Ok(359_282..=820_401));
+    let error = |input| {
+        let e = parse_range(input).unwrap_err();
+        (e.column, e.message)
+    };
+    assert_eq!(
+        error("abc"),
+        (1, "expected low-high, got \"abc\"".to_string())
+    );
+    assert_eq!(error("12-x4"), (4, "invalid bound \"x4\"".to_string()));
+}
+
+answer_tests!(Day4);
@@ -1,3 +1,7 @@
+use crate::intcode::intcode_parser;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
 // NOTE: include_str! will append new line in the end always
 const INPUT: &str = include_str!("./input");
 
@@ -81,26 +85,38 @@ pub fn run(instructions: &mut [isize], inputs: &[isize]) -> Vec<isize> {
     ans
 }
 
-pub fn part_1() -> Option<isize> {
-    let mut instructions: Vec<isize> = INPUT
-        .split(',')
-        .filter_map(|v| v.parse::<isize>().ok())
-        .collect();
+pub fn part_1(input: &str) -> Result<Option<isize>, ParseError> {
+    let mut instructions = intcode_parser(input.trim())?;
     let outputs = run(&mut instructions, &[1]);
 
-    outputs.last().copied()
+    Ok(outputs.last().copied())
 }
 
-pub fn part_2() -> Option<isize> {
-    let mut instructions: Vec<isize> = INPUT
-        .trim()
-        .split(',')
-        // use unwrap to panic on error
-        .map(|v| v.parse::<isize>().unwrap())
-        .collect();
+pub fn part_2(input: &str) -> Result<Option<isize>, ParseError> {
+    let mut instructions = intcode_parser(input.trim())?;
     let outputs = run(&mut instructions, &[5]);
 
-    outputs.last().copied()
+    Ok(outputs.last().copied())
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
@@ -112,12 +128,12 @@ fn test_run_without_input_value() {
 
 #[test]
 fn test_part_1() {
-    assert_eq!(part_1(), Some(9_775_037));
+    assert_eq!(part_1(INPUT), Ok(Some(9_775_037)));
 }
 
 #[test]
 fn test_part_2() {
-    assert_eq!(part_2(), Some(15586959));
+    assert_eq!(part_2(INPUT), Ok(Some(15586959)));
 }
 
 #[test]
@@ -188,3 +204,5 @@ fn test_larger_example() {
     assert_eq!(run(&mut instructions.clone(), &[8]), vec![1000]);
     assert_eq!(run(&mut instructions.clone(), &[9]), vec![1001]);
 }
+
+answer_tests!(Day5);
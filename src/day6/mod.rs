@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
 
 const INPUT: &str = include_str!("./input");
@@ -5,21 +7,26 @@ const INPUT: &str = include_str!("./input");
 type Parent<'a> = &'a str;
 type OrbitMap<'a> = HashMap<&'a str, (Parent<'a>, HashSet<&'a str>)>;
 
-pub fn part_1() -> usize {
-    total_orbits(INPUT)
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    total_orbits(input)
 }
 
-pub fn part_2() -> usize {
-    minimum_transfers(INPUT)
+pub fn part_2(input: &str) -> Result<Option<usize>, ParseError> {
+    minimum_transfers(input)
 }
 
-fn gen_orbits(input: &str) -> OrbitMap {
+fn gen_orbits(input: &str) -> Result<OrbitMap<'_>, ParseError> {
     let mut orbits: OrbitMap = HashMap::new();
 
     for line in input.lines() {
-        let parts: Vec<&str> = line.split(')').collect();
-        let central = parts[0];
-        let follower = parts[1];
+        let (central, follower) = line
+            .split_once(')')
+            .ok_or_else(|| ParseError::at(input, line, format!("expected A)B, got {:?}", line)))?;
+        for name in &[central, follower] {
+            if name.is_empty() {
+                return Err(ParseError::at(input, name, "missing object name"));
+            }
+        }
 
         // Unknown parent yet
         let (_, followers) = orbits.entry(central).or_insert(("", HashSet::new()));
@@ -29,13 +36,13 @@ fn gen_orbits(input: &str) -> OrbitMap {
         *parent = central;
     }
 
-    orbits
+    Ok(orbits)
 }
 
-fn total_orbits(input: &str) -> usize {
-    let orbits: OrbitMap = gen_orbits(input);
+fn total_orbits(input: &str) -> Result<usize, ParseError> {
+    let orbits: OrbitMap = gen_orbits(input)?;
 
-    walk_down_count(&orbits, "COM", 0)
+    Ok(walk_down_count(&orbits, "COM", 0))
 }
 
 fn walk_down_count(orbits: &OrbitMap, central: &str, level: usize) -> usize {
@@ -61,53 +68,61 @@ fn get_parents(orbits: &OrbitMap, obj: &str) -> Vec<String> {
                 ret.push(parent.to_string());
                 central = parent;
             }
-            _ => (),
+            // Chain doesn't reach COM
+            (_, None) => break,
         }
     }
     ret
 }
 
-fn minimum_transfers(input: &str) -> usize {
-    let orbits = gen_orbits(input);
+// None when YOU or SAN aren't in the map
+fn minimum_transfers(input: &str) -> Result<Option<usize>, ParseError> {
+    let orbits = gen_orbits(input)?;
+    if !orbits.contains_key("YOU") || !orbits.contains_key("SAN") {
+        return Ok(None);
+    }
 
-    get_parents(&orbits, "YOU")
-        .iter()
-        .collect::<HashSet<_>>()
-        .symmetric_difference(&get_parents(&orbits, "SAN").iter().collect())
-        .collect::<HashSet<_>>()
-        .len()
+    Ok(Some(
+        get_parents(&orbits, "YOU")
+            .iter()
+            .collect::<HashSet<_>>()
+            .symmetric_difference(&get_parents(&orbits, "SAN").iter().collect())
+            .collect::<HashSet<_>>()
+            .len(),
+    ))
 }
 
-#[test]
-fn test_total_orbits() {
-    let input = r"COM)B
-B)C
-C)D
-D)E
-E)F
-B)G
-G)H
-D)I
-E)J
-J)K
-K)L";
-    assert_eq!(total_orbits(input), 42);
+pub struct Day6;
+
+impl Solution for Day6 {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
-fn test_minimum_transfers() {
-    let input = r"COM)B
-B)C
-C)D
-D)E
-E)F
-B)G
-G)H
-D)I
-E)J
-J)K
-K)L
-K)YOU
-I)SAN";
-    assert_eq!(minimum_transfers(input), 4);
+fn test_gen_orbits_errors() {
+    let error = |input| {
+        let e = gen_orbits(input).map(|_| ()).unwrap_err();
+        (e.line, e.column)
+    };
+    assert_eq!(error("COM)B\nB-C"), (2, 1));
+    assert_eq!(error("COM)B\nB)"), (2, 3));
+    assert_eq!(error("COM)B\n\nB)C"), (2, 1));
+    assert_eq!(part_2("COM)B\nB)C"), Ok(None));
 }
+
+fixture_tests!(Day6);
+answer_tests!(Day6);
@@ -2,32 +2,27 @@ mod permutations;
 
 use super::day5::run;
 use crate::intcode::{intcode_parser, Action, IntcodeProgram, Machine};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use permutations::permutations;
 
 const INPUT: &str = include_str!("./input");
 
-fn signal(program: &Vec<isize>, phase_settings: &[usize]) -> isize {
-    phase_settings.iter().fold(0, |input, setting| {
-        *run(&mut program.clone(), &[*setting as isize, input])
+// None if an amplifier halts without output
+fn signal(program: &[isize], phase_settings: &[usize]) -> Option<isize> {
+    phase_settings.iter().try_fold(0, |input, setting| {
+        run(&mut program.to_vec(), &[*setting as isize, input])
             .last()
-            .unwrap()
+            .copied()
     })
 }
 
-fn process_input(input: &str) -> Vec<isize> {
-    input
-        .trim()
-        .split(',')
-        .map(|v| v.parse::<isize>().expect("Number"))
-        .collect()
-}
-
-fn signal_loop_mode(program: &IntcodeProgram, phase_settings: &[isize]) -> isize {
-    let mut output_a = Machine::new(&program);
-    let mut output_b = Machine::new(&program);
-    let mut output_c = Machine::new(&program);
-    let mut output_d = Machine::new(&program);
-    let mut output_e = Machine::new(&program);
+fn signal_loop_mode(program: &IntcodeProgram, phase_settings: &[isize]) -> Option<isize> {
+    let mut output_a = Machine::new(program);
+    let mut output_b = Machine::new(program);
+    let mut output_c = Machine::new(program);
+    let mut output_d = Machine::new(program);
+    let mut output_e = Machine::new(program);
 
     output_a.push_input(phase_settings[0]);
     output_b.push_input(phase_settings[1]);
@@ -39,11 +34,8 @@ fn signal_loop_mode(program: &IntcodeProgram, phase_settings: &[isize]) -> isize
 
     let mut ret = None;
 
-    loop {
-        match output_a.run() {
-            Action::Output(v) => output_b.push_input(v),
-            Action::Halt => break,
-        }
+    while let Action::Output(v) = output_a.run() {
+        output_b.push_input(v);
         match output_b.run() {
             Action::Output(v) => output_c.push_input(v),
             Action::Halt => break,
@@ -64,19 +56,20 @@ fn signal_loop_mode(program: &IntcodeProgram, phase_settings: &[isize]) -> isize
             Action::Halt => break,
         }
     }
-    ret.expect("No output")
+    ret
 }
 
-pub fn part_1() -> isize {
-    let program = process_input(INPUT);
+pub fn part_1(input: &str) -> Result<isize, ParseError> {
+    let program = intcode_parser(input.trim())?;
     permutations(5)
         .map(|settings| signal(&program, &settings))
-        .max()
-        .unwrap()
+        .collect::<Option<Vec<_>>>()
+        .and_then(|signals| signals.into_iter().max())
+        .ok_or_else(|| ParseError::at_end(input.trim(), "no output"))
 }
 
-pub fn part_2() -> isize {
-    let program = intcode_parser(INPUT.trim());
+pub fn part_2(input: &str) -> Result<isize, ParseError> {
+    let program = intcode_parser(input.trim())?;
     permutations(5)
         .map(|settings| {
             settings
@@ -85,67 +78,41 @@ pub fn part_2() -> isize {
                 .collect::<Vec<_>>()
         })
         .map(|v| signal_loop_mode(&program, &v))
-        .max()
-        .unwrap()
+        .collect::<Option<Vec<_>>>()
+        .and_then(|signals| signals.into_iter().max())
+        .ok_or_else(|| ParseError::at_end(input.trim(), "no output"))
 }
 
-#[test]
-fn test_part_1() {
-    assert_eq!(part_1(), 880_726);
+pub struct Day7;
+
+impl Solution for Day7 {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
-fn test_signal_loop() {
-    assert_eq!(
-        signal_loop_mode(
-            &vec![
-                3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28,
-                -1, 28, 1005, 28, 6, 99, 0, 0, 5
-            ],
-            &[9, 8, 7, 6, 5]
-        ),
-        139_629_729
-    );
-    assert_eq!(
-        signal_loop_mode(
-            &vec![
-                3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001,
-                54, -5, 54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54, 0, 55, 1001, 55, 1, 55, 2, 53,
-                55, 53, 4, 53, 1001, 56, -1, 56, 1005, 56, 6, 99, 0, 0, 0, 0, 10
-            ],
-            &[9, 7, 8, 5, 6]
-        ),
-        18216
-    );
+fn test_part_1() {
+    assert_eq!(part_1(INPUT), Ok(880_726));
 }
 
 #[test]
-fn test_signal() {
-    assert_eq!(
-        signal(
-            &vec![3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0],
-            &[4, 3, 2, 1, 0]
-        ),
-        43210
-    );
-    assert_eq!(
-        signal(
-            &vec![
-                3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4,
-                23, 99, 0, 0
-            ],
-            &[0, 1, 2, 3, 4]
-        ),
-        54321
-    );
-    assert_eq!(
-        signal(
-            &vec![
-                3, 31, 3, 32, 1002, 32, 10, 32, 1001, 31, -2, 31, 1007, 31, 0, 33, 1002, 33, 7, 33,
-                1, 33, 31, 31, 1, 32, 31, 31, 4, 31, 99, 0, 0, 0
-            ],
-            &[1, 0, 4, 3, 2]
-        ),
-        65210
-    );
+fn test_no_output() {
+    assert_eq!(part_1("99\n").unwrap_err().message, "no output");
+    assert_eq!(part_2("99\n").unwrap_err().message, "no output");
 }
+
+fixture_tests!(Day7);
+answer_tests!(Day7);
@@ -1,21 +1,40 @@
+mod palette;
+mod space_image;
+
+use crate::grid::Grid;
+use crate::image::{self, Rgb};
+use crate::ocr;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+pub use palette::Palette;
+pub use space_image::SpaceImage;
+use std::convert::TryFrom;
 use Color::*;
 
 const INPUT: &str = include_str!("./input");
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Color {
+pub enum Color {
     Black = 0,
     White = 1,
     Transparent = 2,
 }
 
-impl From<u8> for Color {
-    fn from(v: u8) -> Self {
+impl From<Color> for u8 {
+    fn from(color: Color) -> u8 {
+        color as u8
+    }
+}
+
+impl TryFrom<u8> for Color {
+    type Error = u8;
+
+    fn try_from(v: u8) -> Result<Self, u8> {
         match v {
-            0 => Black,
-            1 => White,
-            2 => Transparent,
-            _ => panic!("Invalid color {}", v),
+            0 => Ok(Black),
+            1 => Ok(White),
+            2 => Ok(Transparent),
+            _ => Err(v),
         }
     }
 }
@@ -33,61 +52,110 @@ fn mix_pixel_color_in_layers(colors: &[Color]) -> Color {
         .fold(Transparent, |first, second| mix_pixel_color(first, *second))
 }
 
-fn split_layers(input: &str, w: usize, h: usize) -> Vec<Vec<u8>> {
-    input
-        .trim()
-        .chars()
-        .map(|c| c.to_digit(10).expect("invalid input") as u8)
-        .collect::<Vec<_>>()
-        .chunks(w * h)
-        .map(|v| v.to_vec())
-        .collect()
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    let image = SpaceImage::parse(input, WIDTH, HEIGHT)?;
+    let stats = image.layer_stats();
+    let layer = stats.iter().min_by_key(|layer| layer.black).unwrap();
+    Ok(layer.white * layer.transparent)
 }
 
-pub fn part_1() -> usize {
-    let count_digits = |layer: &Vec<u8>, digit| layer.iter().filter(|v| **v == digit).count();
-    let layers = split_layers(INPUT, 25, 6);
-    let layer = layers
-        .iter()
-        .min_by_key(|layer| count_digits(layer, 0))
-        .unwrap();
-    count_digits(&layer, 1) * count_digits(&layer, 2)
-}
-
-pub fn part_2() -> String {
-    let w: usize = 25;
-    let h: usize = 6;
-    let layers = split_layers(INPUT, w, h);
-    (0..w * h)
-        .map(|i| {
-            layers
-                .iter()
-                .filter_map(|layer| layer.get(i).map(|v| Color::from(*v)))
-                .collect::<Vec<_>>()
-        })
-        .map(|colors| match mix_pixel_color_in_layers(&colors) {
-            White => "⬜️",
-            Black => "⬛️",
-            _ => " ",
+fn decode(input: &str) -> Result<Grid<Color>, ParseError> {
+    Ok(SpaceImage::parse(input, WIDTH, HEIGHT)?.flatten())
+}
+
+fn render(pixels: &Grid<Color>) -> String {
+    pixels
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|color| match color {
+                    White => "⬜️",
+                    Black => "⬛️",
+                    _ => " ",
+                })
+                .collect::<String>()
         })
         .collect::<Vec<_>>()
-        .chunks(w)
-        .map(|rows| rows.iter().map(|c| c.to_string()).collect::<String>())
-        .collect::<Vec<_>>()
         .join("\n")
 }
 
-#[test]
-fn test_split_layers() {
-    assert_eq!(
-        split_layers("123456789012", 3, 2),
-        vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 0, 1, 2]]
-    );
+// The letters in the image, or the image itself when they can't be read
+pub fn part_2(input: &str) -> Result<String, ParseError> {
+    let pixels = decode(input)?;
+    Ok(ocr::recognize(&pixels.map(|color| *color == White))
+        .unwrap_or_else(|e| format!("{}\n{}", render(&pixels), e)))
+}
+
+const GRAY: Rgb = Rgb(128, 128, 128);
+
+// Transparent pixels, where every layer is transparent, come out gray
+fn to_rgb(color: &Color) -> Rgb {
+    match color {
+        White => image::WHITE,
+        Black => image::BLACK,
+        Transparent => GRAY,
+    }
+}
+
+pub fn image(input: &str) -> Result<Grid<Rgb>, ParseError> {
+    Ok(decode(input)?.map(to_rgb))
+}
+
+// Any digits, blended by their alpha over a gray background. The default palette gives
+// the same picture as `image`.
+pub fn composite(input: &str, palette: &Palette) -> Result<Grid<Rgb>, ParseError> {
+    let unknown = |c: char| {
+        c.to_digit(10)
+            .is_some_and(|d| palette.get(d as u8).is_none())
+    };
+    if let Some(i) = input.find(unknown) {
+        return Err(ParseError::at(
+            input,
+            &input[i..],
+            format!("digit {} isn't in the palette", &input[i..i + 1]),
+        ));
+    }
+    let image = SpaceImage::<u8>::parse(input, WIDTH, HEIGHT)?;
+    Ok(palette.composite(&image, GRAY))
+}
+
+// The image after stacking the first layer, the first two, and so on
+pub fn frames(input: &str) -> Result<Vec<Grid<Rgb>>, ParseError> {
+    let image = SpaceImage::parse(input, WIDTH, HEIGHT)?;
+    Ok(image.frames().iter().map(|f| f.map(to_rgb)).collect())
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
 }
 
 #[test]
 fn test_mix_pixel_color_in_layers() {
-    let to_color = |input: Vec<u8>| input.into_iter().map(Color::from).collect::<Vec<_>>();
+    let to_color = |input: Vec<u8>| {
+        input
+            .into_iter()
+            .map(|v| Color::try_from(v).unwrap())
+            .collect::<Vec<_>>()
+    };
     assert_eq!(
         mix_pixel_color_in_layers(&to_color(vec![0, 1, 2, 0])),
         Black
@@ -108,5 +176,17 @@ fn test_mix_pixel_color_in_layers() {
 
 #[test]
 fn test_part_1() {
-    assert_eq!(part_1(), 2975);
+    assert_eq!(part_1(INPUT), Ok(2975));
+}
+
+#[test]
+fn test_composite() {
+    assert_eq!(composite(INPUT, &Palette::default()), image(INPUT));
+    let e = composite("0123", &Palette::default()).unwrap_err();
+    assert_eq!(
+        (e.column, e.message.as_str()),
+        (4, "digit 3 isn't in the palette")
+    );
 }
+
+answer_tests!(Day8);
@@ -0,0 +1,121 @@
+use super::SpaceImage;
+use crate::grid::Grid;
+use crate::image::{Rgb, Rgba};
+
+// Color of every digit, digits without one can't be composited
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: [Option<Rgba>; 10],
+}
+
+// The puzzle's colors: black, white and transparent
+impl Default for Palette {
+    fn default() -> Self {
+        let mut colors = [None; 10];
+        colors[0] = Some(Rgba(0, 0, 0, 255));
+        colors[1] = Some(Rgba(255, 255, 255, 255));
+        colors[2] = Some(Rgba(0, 0, 0, 0));
+        Palette { colors }
+    }
+}
+
+fn hex_color(value: &str) -> Option<Rgba> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Rgba(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+impl Palette {
+    // The default palette with `digit = "#rrggbb"` or `digit = "#rrggbbaa"` lines laid over it,
+    // lines starting with `#` are comments
+    pub fn parse(text: &str) -> Result<Palette, String> {
+        let mut palette = Palette::default();
+        for (i, line) in text.lines().enumerate() {
+            let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected digit = \"#rrggbb\""))?;
+            let digit = match key.trim().parse::<usize>() {
+                Ok(d) if d < 10 => d,
+                _ => return Err(error(&format!("invalid digit {}", key.trim()))),
+            };
+            let value = value.trim();
+            let color = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .and_then(hex_color)
+                .ok_or_else(|| error(&format!("invalid color {}", value)))?;
+            palette.colors[digit] = Some(color);
+        }
+        Ok(palette)
+    }
+
+    pub fn get(&self, digit: u8) -> Option<Rgba> {
+        self.colors.get(digit as usize).copied().flatten()
+    }
+
+    // Layers are laid front to back with the "over" operator, then over `background`.
+    // Panics on digits the palette has no color for.
+    pub fn composite(&self, image: &SpaceImage<u8>, background: Rgb) -> Grid<Rgb> {
+        let mut composite = Grid::new(image.width(), image.height(), background);
+        for (p, pixel) in composite.clone().iter() {
+            // Premultiplied red, green, blue and the coverage so far, all from 0 to 1
+            let mut acc = [0.0; 4];
+            for layer in image.layers() {
+                let digit = layer[p];
+                let Rgba(r, g, b, a) = self
+                    .get(digit)
+                    .unwrap_or_else(|| panic!("digit {} isn't in the palette", digit));
+                let weight = (1.0 - acc[3]) * a as f64 / 255.0;
+                for (channel, value) in acc.iter_mut().zip(&[r, g, b, 255]) {
+                    *channel += weight * *value as f64 / 255.0;
+                }
+                if acc[3] >= 1.0 {
+                    break;
+                }
+            }
+            let Rgb(r, g, b) = *pixel;
+            let blend = |i: usize, back: u8| {
+                ((acc[i] + (1.0 - acc[3]) * back as f64 / 255.0) * 255.0).round() as u8
+            };
+            composite[p] = Rgb(blend(0, r), blend(1, g), blend(2, b));
+        }
+        composite
+    }
+}
+
+#[test]
+fn test_parse() {
+    let palette = Palette::parse("# warm\n3 = \"#ff000080\"\n\n0 = \"#102030\"\n").unwrap();
+    assert_eq!(palette.get(3), Some(Rgba(255, 0, 0, 128)));
+    assert_eq!(palette.get(0), Some(Rgba(16, 32, 48, 255)));
+    assert_eq!(palette.get(2), Palette::default().get(2));
+    assert_eq!(palette.get(9), None);
+
+    assert_eq!(
+        Palette::parse("1 = \"#fff\""),
+        Err("line 1: invalid color \"#fff\"".to_string())
+    );
+    assert_eq!(
+        Palette::parse("\n10 = \"#ffffff\""),
+        Err("line 2: invalid digit 10".to_string())
+    );
+}
+
+#[test]
+fn test_composite() {
+    let palette = Palette::parse("3 = \"#ff000080\"\n4 = \"#0000ff80\"").unwrap();
+    // Half red in front of half blue, then half red in front of opaque white
+    let image = SpaceImage::<u8>::parse("3341", 2, 1).unwrap();
+    let composite = palette.composite(&image, Rgb(0, 0, 0));
+    let pixels = composite.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+    assert_eq!(pixels, vec![Rgb(128, 0, 64), Rgb(255, 127, 127)]);
+}
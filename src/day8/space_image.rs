@@ -0,0 +1,227 @@
+use super::{mix_pixel_color, mix_pixel_color_in_layers, Color};
+use crate::grid::{Grid, Point};
+use crate::parse::ParseError;
+use std::convert::TryFrom;
+use std::fmt;
+
+// Space Image Format: one digit per pixel, row by row, layer after layer.
+// Pixels are `Color`s for the puzzle, or plain digits to look up in a `Palette`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpaceImage<T = Color> {
+    width: usize,
+    height: usize,
+    layers: Vec<Grid<T>>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LayerStats {
+    pub black: usize,
+    pub white: usize,
+    pub transparent: usize,
+}
+
+impl<T: Copy> SpaceImage<T> {
+    // Every layer must be `width` by `height`
+    pub fn new(width: usize, height: usize, layers: Vec<Grid<T>>) -> Self {
+        for layer in &layers {
+            assert_eq!((layer.width(), layer.height()), (width, height));
+        }
+        SpaceImage {
+            width,
+            height,
+            layers,
+        }
+    }
+
+    pub fn parse(input: &str, width: usize, height: usize) -> Result<Self, ParseError>
+    where
+        T: TryFrom<u8>,
+    {
+        let digits = input.trim_end();
+        let mut pixels = vec![];
+        for (i, c) in digits.char_indices() {
+            let pixel = c
+                .to_digit(10)
+                .and_then(|d| T::try_from(d as u8).ok())
+                .ok_or_else(|| {
+                    ParseError::at(digits, &digits[i..], format!("invalid pixel {:?}", c))
+                })?;
+            pixels.push(pixel);
+        }
+        if width * height == 0 || !pixels.len().is_multiple_of(width * height) {
+            return Err(ParseError::at_end(
+                digits,
+                format!(
+                    "{} digits don't make whole {}x{} layers",
+                    pixels.len(),
+                    width,
+                    height
+                ),
+            ));
+        }
+        if pixels.is_empty() {
+            return Err(ParseError::at_end(digits, "no layers"));
+        }
+
+        let layers = pixels
+            .chunks(width * height)
+            .map(|layer| Grid::from_vec(width, layer.to_vec()))
+            .collect();
+        Ok(SpaceImage::new(width, height, layers))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Front to back
+    pub fn layers(&self) -> &[Grid<T>] {
+        &self.layers
+    }
+}
+
+impl SpaceImage<Color> {
+    pub fn layer_stats(&self) -> Vec<LayerStats> {
+        self.layers
+            .iter()
+            .map(|layer| {
+                let mut stats = LayerStats::default();
+                for (_, color) in layer.iter() {
+                    match color {
+                        Color::Black => stats.black += 1,
+                        Color::White => stats.white += 1,
+                        Color::Transparent => stats.transparent += 1,
+                    }
+                }
+                stats
+            })
+            .collect()
+    }
+
+    // Layers stacked with the first one in front
+    pub fn flatten(&self) -> Grid<Color> {
+        let mut image = Grid::new(self.width, self.height, Color::Transparent);
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                let p = Point::new(x, y);
+                let colors = self.layers.iter().map(|l| l[p]).collect::<Vec<_>>();
+                image[p] = mix_pixel_color_in_layers(&colors);
+            }
+        }
+        image
+    }
+
+    // What `flatten` has built after each layer, the last frame is the flattened image
+    pub fn frames(&self) -> Vec<Grid<Color>> {
+        let mut image = Grid::new(self.width, self.height, Color::Transparent);
+        self.layers
+            .iter()
+            .map(|layer| {
+                for (p, color) in layer.iter() {
+                    image[p] = mix_pixel_color(image[p], *color);
+                }
+                image.clone()
+            })
+            .collect()
+    }
+}
+
+// The digits `parse` reads back
+impl<T: Copy> fmt::Display for SpaceImage<T>
+where
+    u8: From<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for layer in &self.layers {
+            for (_, pixel) in layer.iter() {
+                write!(f, "{}", u8::from(*pixel))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_split_layers() {
+    // The puzzle's example
+    let image = SpaceImage::<u8>::parse("123456789012", 3, 2).unwrap();
+    assert_eq!(
+        image.layers(),
+        &[
+            Grid::from_vec(3, vec![1, 2, 3, 4, 5, 6]),
+            Grid::from_vec(3, vec![7, 8, 9, 0, 1, 2])
+        ]
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    use Color::*;
+
+    // A mostly transparent front layer with one black pixel over an opaque back layer
+    let front = Grid::from_vec(
+        3,
+        vec![
+            Transparent,
+            Transparent,
+            Transparent,
+            Transparent,
+            Black,
+            Transparent,
+        ],
+    );
+    let back = Grid::from_vec(3, vec![Black, White, Black, White, White, White]);
+    let image = SpaceImage::new(3, 2, vec![front, back]);
+    assert_eq!(image.to_string(), "222202010111");
+    assert_eq!(SpaceImage::parse("222202010111\n", 3, 2), Ok(image.clone()));
+    let digits = SpaceImage::<u8>::parse("987650", 3, 2).unwrap();
+    assert_eq!(digits.to_string(), "987650");
+
+    assert_eq!(
+        image.flatten(),
+        Grid::from_vec(3, vec![Black, White, Black, White, Black, White])
+    );
+    let frames = image.frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(
+        frames[0],
+        Grid::from_vec(
+            3,
+            vec![
+                Transparent,
+                Transparent,
+                Transparent,
+                Transparent,
+                Black,
+                Transparent
+            ]
+        )
+    );
+    assert_eq!(frames[1], image.flatten());
+    assert_eq!(
+        image.layer_stats()[0],
+        LayerStats {
+            black: 1,
+            white: 0,
+            transparent: 5
+        }
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = |input, w, h| {
+        let e = SpaceImage::<Color>::parse(input, w, h).unwrap_err();
+        (e.column, e.message)
+    };
+    assert_eq!(
+        error("1201201", 3, 2),
+        (8, "7 digits don't make whole 3x2 layers".to_string())
+    );
+    assert_eq!(error("012312", 3, 2), (4, "invalid pixel '3'".to_string()));
+    assert_eq!(error("\n", 3, 2), (1, "no layers".to_string()));
+}
@@ -1,23 +1,83 @@
-use crate::intcode::{intcode_parser, Action, Machine};
+use crate::intcode::{
+    intcode_parser, Action, ControlFlowGraph, DecodedMachine, Machine, SelfModifyingLog,
+};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 
 const INPUT: &str = include_str!("./input");
 
-pub fn part_1() -> isize {
-    let program = intcode_parser(INPUT.trim());
+pub fn part_1(input: &str) -> Result<isize, ParseError> {
+    let program = intcode_parser(input.trim())?;
     let mut machine = Machine::with_capacity(&program, 2000);
     machine.push_input(1);
     match machine.run() {
-        Action::Output(v) => v,
-        Action::Halt => panic!("No output"),
+        Action::Output(v) => Ok(v),
+        Action::Halt => Err(ParseError::at_end(input.trim(), "no output")),
     }
 }
 
-pub fn part_2() -> isize {
-    let program = intcode_parser(INPUT.trim());
-    let mut machine = Machine::with_capacity(&program, 2000);
+pub fn part_2(input: &str) -> Result<isize, ParseError> {
+    let program = intcode_parser(input.trim())?;
+    let mut machine = DecodedMachine::with_capacity(&program, 2000);
     machine.push_input(2);
     match machine.run() {
-        Action::Output(v) => v,
-        Action::Halt => panic!("No output"),
+        Action::Output(v) => Ok(v),
+        Action::Halt => Err(ParseError::at_end(input.trim(), "no output")),
+    }
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn input(&self) -> &'static str {
+        INPUT
     }
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        part_1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        part_2(input).map(Answer::from)
+    }
+}
+
+pub fn self_modifying() -> SelfModifyingLog {
+    let program = intcode_parser(INPUT.trim()).unwrap();
+    let mut machine = Machine::with_capacity(&program, 2000);
+    machine.track_self_modifying();
+    machine.push_input(2);
+    while let Action::Output(_) = machine.run() {}
+    machine.into_self_modifying().unwrap()
+}
+
+pub fn cfg() -> String {
+    ControlFlowGraph::new(&intcode_parser(INPUT.trim()).unwrap()).to_dot()
 }
+
+// cargo test --release bench_part_2 -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_part_2() {
+    use std::time::Instant;
+
+    let program = intcode_parser(INPUT.trim()).unwrap();
+
+    let start = Instant::now();
+    let mut machine = Machine::with_capacity(&program, 2000);
+    machine.push_input(2);
+    let expected = machine.run();
+    println!("Machine: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let mut machine = DecodedMachine::with_capacity(&program, 2000);
+    machine.push_input(2);
+    assert_eq!(machine.run(), expected);
+    println!("DecodedMachine: {:?}", start.elapsed());
+}
+
+answer_tests!(Day9);
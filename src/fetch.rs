@@ -0,0 +1,195 @@
+use crate::input::InputProvider;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+// Session cookie of a logged in adventofcode.com account
+pub const SESSION_ENV: &str = "AOC_SESSION";
+// Overrides where inputs are downloaded from, e.g. a local stand-in server
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+pub trait HttpBackend {
+    // Body of a `200 OK` response, anything else is an error
+    fn get(&self, url: &str, headers: &[(&str, String)]) -> Result<String, String>;
+}
+
+// Plain HTTP/1.1 over a TCP socket, no TLS and no chunked responses
+pub struct TcpBackend;
+
+impl HttpBackend for TcpBackend {
+    fn get(&self, url: &str, headers: &[(&str, String)]) -> Result<String, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or(format!("Not an http:// url {}", url))?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+
+        let mut request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+            path, host
+        );
+        for (name, value) in headers {
+            request += &format!("{}: {}\r\n", name, value);
+        }
+        request += "\r\n";
+
+        let mut stream = TcpStream::connect(&address).map_err(|e| format!("{}: {}", address, e))?;
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|e| e.to_string())?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("Malformed HTTP response")?;
+        let status = head.lines().next().unwrap_or("");
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("{}: {}", url, status));
+        }
+        if head.to_lowercase().contains("transfer-encoding: chunked") {
+            return Err("Chunked responses aren't supported".to_string());
+        }
+        Ok(body.to_string())
+    }
+}
+
+// Shells out to `curl`, for https:// urls like the real adventofcode.com.
+// Headers go through curl's stdin, so the session cookie doesn't show up in `ps`.
+pub struct CurlBackend;
+
+impl HttpBackend for CurlBackend {
+    fn get(&self, url: &str, headers: &[(&str, String)]) -> Result<String, String> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Can't run curl: {}", e))?;
+        let mut stdin = child.stdin.take().unwrap();
+        for (name, value) in headers {
+            writeln!(stdin, "{}: {}", name, value).map_err(|e| e.to_string())?;
+        }
+        // Closing stdin lets curl start the request
+        drop(stdin);
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Can't run curl: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        String::from_utf8(output.stdout).map_err(|e| e.to_string())
+    }
+}
+
+pub fn backend_for(url: &str) -> Box<dyn HttpBackend> {
+    if url.starts_with("http://") {
+        Box::new(TcpBackend)
+    } else {
+        Box::new(CurlBackend)
+    }
+}
+
+// Downloads into `<cache_dir>/day<N>/input`, the layout `InputProvider` reads from.
+// A cached input is never fetched again.
+pub struct Fetcher {
+    backend: Box<dyn HttpBackend>,
+    base_url: String,
+    session: String,
+    cache_dir: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(
+        backend: Box<dyn HttpBackend>,
+        base_url: &str,
+        session: &str,
+        cache_dir: PathBuf,
+    ) -> Self {
+        Fetcher {
+            backend,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            cache_dir,
+        }
+    }
+
+    pub fn fetch(&self, day: u8) -> Result<String, String> {
+        let path = InputProvider::day_path(&self.cache_dir, day);
+        if path.exists() {
+            return fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read {}: {}", path.display(), e));
+        }
+
+        let url = format!("{}/2019/day/{}/input", self.base_url, day);
+        let input = self
+            .backend
+            .get(&url, &[("Cookie", format!("session={}", self.session))])?;
+
+        let write = || -> std::io::Result<()> {
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, &input)
+        };
+        write().map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+        Ok(input)
+    }
+}
+
+#[test]
+fn test_fetch_from_stand_in_server() {
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    // Serves two requests and hands back the request heads it saw
+    let server = thread::spawn(move || {
+        let mut requests = vec![];
+        for stream in listener.incoming().take(2) {
+            let mut stream = stream.unwrap();
+            let head = BufReader::new(&stream)
+                .lines()
+                .map(|line| line.unwrap())
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            let response = if head[0].starts_with("GET /2019/day/6/input ") {
+                "HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\nCOM)B\n"
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+            requests.push(head);
+        }
+        requests
+    });
+
+    let dir = std::env::temp_dir().join(format!("aoc-2019-fetch-{}", std::process::id()));
+    let fetcher = Fetcher::new(Box::new(TcpBackend), &base_url, "token", dir.clone());
+    assert_eq!(fetcher.fetch(6), Ok("COM)B\n".to_string()));
+    // Served from the cache, the server only ever sees one request for day 6
+    assert_eq!(fetcher.fetch(6), Ok("COM)B\n".to_string()));
+    assert!(fetcher.fetch(7).is_err());
+    assert!(!InputProvider::day_path(&dir, 7).exists());
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].contains(&"Cookie: session=token".to_string()));
+    assert!(requests[1][0].starts_with("GET /2019/day/7/input "));
+
+    fs::remove_dir_all(dir).unwrap();
+}
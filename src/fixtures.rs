@@ -0,0 +1,84 @@
+// Puzzle examples live in `fixtures/day<N>/*.txt`: a header of `partN = <answer>` lines,
+// a `---` line, then the example input. Parts without a header line aren't run, since
+// some examples only make sense for one part.
+use crate::solution::Solution;
+use std::{fs, path::Path};
+
+#[derive(Debug, PartialEq)]
+pub struct Fixture {
+    pub expected: Vec<(u8, String)>,
+    pub input: String,
+}
+
+pub fn parse(fixture: &str) -> Result<Fixture, String> {
+    let (header, input) = fixture
+        .split_once("---\n")
+        .ok_or("Missing --- line between answers and input")?;
+    let expected = header
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("Expected partN = answer, got {}", line))?;
+            match key.trim() {
+                "part1" => Ok((1, value.trim().to_string())),
+                "part2" => Ok((2, value.trim().to_string())),
+                key => Err(format!("Unknown key {}", key)),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Fixture {
+        expected,
+        input: input.to_string(),
+    })
+}
+
+pub fn check(solution: &dyn Solution) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(format!("day{}", solution.day()));
+    let mut paths = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Can't read {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "No fixtures in {}", dir.display());
+
+    let mut failures = vec![];
+    for path in paths {
+        let fixture = parse(&fs::read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        for (part, expected) in fixture.expected {
+            let actual = match part {
+                1 => solution.part1(&fixture.input),
+                _ => solution.part2(&fixture.input),
+            };
+            let actual = actual.map_or_else(|e| e.to_string(), |answer| answer.to_string());
+            if actual != expected {
+                failures.push(format!(
+                    "{} part {}: expected {}, got {}",
+                    path.display(),
+                    part,
+                    expected,
+                    actual
+                ));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        parse("part1 = 42\n\npart2 = 4\n---\nCOM)B\n"),
+        Ok(Fixture {
+            expected: vec![(1, "42".to_string()), (2, "4".to_string())],
+            input: "COM)B\n".to_string()
+        })
+    );
+    assert!(parse("part1 = 42\nCOM)B\n").is_err());
+    assert!(parse("part3 = 42\n---\nCOM)B\n").is_err());
+}
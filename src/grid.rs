@@ -0,0 +1,352 @@
+use crate::parse::ParseError;
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Sub};
+
+// Screen coordinates: x grows to the right, y grows down
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    // Up, right, down and left of this point
+    #[allow(dead_code)] // Not needed by any day yet
+    pub fn neighbors(self) -> impl Iterator<Item = Point> {
+        DIRECTIONS.iter().map(move |d| self + d.offset())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, n: i32) -> Point {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+impl Div<i32> for Point {
+    type Output = Point;
+
+    fn div(self, n: i32) -> Point {
+        Point::new(self.x / n, self.y / n)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+// Clockwise from up
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn turn(self, turn: Turn) -> Direction {
+        let i = DIRECTIONS.iter().position(|d| *d == self).unwrap();
+        match turn {
+            Turn::Right => DIRECTIONS[(i + 1) % 4],
+            Turn::Left => DIRECTIONS[(i + 3) % 4],
+        }
+    }
+
+    // One step in this direction
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+// Inclusive on both ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    // None for no points
+    pub fn of(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, p| {
+            let BoundingBox { min, max } = bounds.unwrap_or(BoundingBox { min: p, max: p });
+            Some(BoundingBox {
+                min: Point::new(min.x.min(p.x), min.y.min(p.y)),
+                max: Point::new(max.x.max(p.x), max.y.max(p.y)),
+            })
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+}
+
+// Every cell from (0, 0) to (width - 1, height - 1), stored row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // `cells` row by row
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells don't fill rows of {}",
+            cells.len(),
+            width
+        );
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    // One row per line, every line as long as the first
+    pub fn parse(input: &str, cell: impl Fn(char) -> T) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+        for line in input.lines() {
+            let len = line.chars().count();
+            if *width.get_or_insert(len) != len {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    format!("expected {} cells, got {}", width.unwrap(), len),
+                ));
+            }
+            cells.extend(line.chars().map(&cell));
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, v)| {
+            let p = Point::new((i % width) as i32, (i / width) as i32);
+            (p, v)
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, p: Point) -> usize {
+        assert!(self.contains(p), "{:?} is outside the grid", p);
+        p.y as usize * self.width + p.x as usize
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        &self.cells[self.index_of(p)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let i = self.index_of(p);
+        &mut self.cells[i]
+    }
+}
+
+// Only the cells that were set, for grids without known bounds
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, v: T) -> Option<T> {
+        self.cells.insert(p, v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::of(self.cells.keys().copied())
+    }
+
+    // Cropped to the bounding box, so the top-left set cell ends up at (0, 0).
+    // None when nothing was set.
+    pub fn to_dense(&self, fill: T) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        let bounds = self.bounding_box()?;
+        let mut grid = Grid::new(bounds.width(), bounds.height(), fill);
+        for (p, v) in &self.cells {
+            grid[*p - bounds.min] = v.clone();
+        }
+        Some(grid)
+    }
+}
+
+#[test]
+fn test_point_arithmetic() {
+    let p = Point::new(3, -2);
+    assert_eq!(p + Point::new(1, 1), Point::new(4, -1));
+    assert_eq!(p - Point::new(1, 1), Point::new(2, -3));
+    assert_eq!(p * 2 / 2, p);
+    assert_eq!(
+        Point::new(0, 0).neighbors().collect::<Vec<_>>(),
+        vec![
+            Point::new(0, -1),
+            Point::new(1, 0),
+            Point::new(0, 1),
+            Point::new(-1, 0)
+        ]
+    );
+}
+
+#[test]
+fn test_turn() {
+    assert_eq!(Direction::Up.turn(Turn::Left), Direction::Left);
+    assert_eq!(Direction::Left.turn(Turn::Right), Direction::Up);
+    assert_eq!(Direction::Down.turn(Turn::Right), Direction::Left);
+}
+
+#[test]
+fn test_grid() {
+    let grid = Grid::parse(".#.\n..#\n", |c| c == '#').unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert!(grid[Point::new(2, 1)]);
+    assert!(!grid.contains(Point::new(3, 0)));
+    assert_eq!(
+        grid.iter()
+            .filter(|(_, v)| **v)
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>(),
+        vec![Point::new(1, 0), Point::new(2, 1)]
+    );
+    let e = Grid::parse("..\n...", |c| c).unwrap_err();
+    assert_eq!((e.line, e.column), (2, 1));
+}
+
+#[test]
+fn test_sparse_grid() {
+    let mut grid = SparseGrid::new();
+    assert_eq!(grid.to_dense('.'), None);
+    grid.insert(Point::new(-1, 2), '#');
+    grid.insert(Point::new(1, 3), '#');
+    assert_eq!(
+        grid.bounding_box(),
+        Some(BoundingBox {
+            min: Point::new(-1, 2),
+            max: Point::new(1, 3)
+        })
+    );
+    let dense = grid.to_dense('.').unwrap();
+    let rows = dense
+        .rows()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, vec!["#..", "..#"]);
+}
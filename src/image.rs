@@ -0,0 +1,423 @@
+use crate::grid::{Grid, Point};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+// Straight (not premultiplied) alpha, 0 is fully transparent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba(pub u8, pub u8, pub u8, pub u8);
+
+pub const BLACK: Rgb = Rgb(0, 0, 0);
+pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+impl Rgb {
+    // Rec. 601 luma, what PGM and PBM are written from
+    fn luma(self) -> u8 {
+        let Rgb(r, g, b) = self;
+        ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    // Netpbm bitmap, pixels darker than mid gray are black
+    Pbm,
+    Pgm,
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "ppm" => Some(Format::Ppm),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+}
+
+// Every pixel becomes an n by n block, the puzzle images are only a few pixels high
+pub fn scale(image: &Grid<Rgb>, n: usize) -> Grid<Rgb> {
+    let mut scaled = Grid::new(image.width() * n, image.height() * n, BLACK);
+    for (p, color) in image.iter() {
+        for dy in 0..n as i32 {
+            for dx in 0..n as i32 {
+                scaled[Point::new(p.x * n as i32 + dx, p.y * n as i32 + dy)] = *color;
+            }
+        }
+    }
+    scaled
+}
+
+pub fn encode(image: &Grid<Rgb>, format: Format) -> Vec<u8> {
+    let header = |magic| format!("{}\n{} {}\n", magic, image.width(), image.height()).into_bytes();
+    match format {
+        Format::Pbm => {
+            let mut bytes = header("P4");
+            // Each row padded to whole bytes, a set bit is black
+            for row in image.rows() {
+                for byte in row.chunks(8) {
+                    bytes.push(byte.iter().enumerate().fold(0, |bits, (i, color)| {
+                        bits | (((color.luma() < 128) as u8) << (7 - i))
+                    }));
+                }
+            }
+            bytes
+        }
+        Format::Pgm => {
+            let mut bytes = header("P5");
+            bytes.extend(b"255\n");
+            bytes.extend(image.iter().map(|(_, color)| color.luma()));
+            bytes
+        }
+        Format::Ppm => {
+            let mut bytes = header("P6");
+            bytes.extend(b"255\n");
+            for (_, Rgb(r, g, b)) in image.iter() {
+                bytes.extend(&[*r, *g, *b]);
+            }
+            bytes
+        }
+        Format::Png => png(image),
+    }
+}
+
+// 8 bit RGB, no filtering, and zlib data made of stored (uncompressed) deflate blocks
+fn png(image: &Grid<Rgb>) -> Vec<u8> {
+    let mut raw = vec![];
+    for row in image.rows() {
+        // Filter type None
+        raw.push(0);
+        for Rgb(r, g, b) in row {
+            raw.extend(&[*r, *g, *b]);
+        }
+    }
+
+    let mut ihdr = vec![];
+    ihdr.extend(&(image.width() as u32).to_be_bytes());
+    ihdr.extend(&(image.height() as u32).to_be_bytes());
+    // Bit depth, color type RGB, compression, filter and interlace methods
+    ihdr.extend(&[8, 2, 0, 0, 0]);
+
+    let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut bytes, b"IHDR", &ihdr);
+    chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+    chunk(&mut bytes, b"IEND", &[]);
+    bytes
+}
+
+fn chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend(&(data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary, fastest level
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        bytes.extend(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        bytes.push(last as u8);
+        bytes.extend(&len.to_le_bytes());
+        bytes.extend(&(!len).to_le_bytes());
+        bytes.extend(block);
+    }
+    bytes.extend(&adler32(data).to_be_bytes());
+    bytes
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1, 0), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+// Animated GIF, looping forever, `delay` is in hundredths of a second per frame.
+// Fails when the frames have more than 256 colors between them.
+pub fn gif(frames: &[Grid<Rgb>], delay: u16) -> Result<Vec<u8>, String> {
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |frame| (frame.width(), frame.height()));
+    let mut palette = vec![];
+    for frame in frames {
+        assert_eq!((frame.width(), frame.height()), (width, height));
+        for (_, color) in frame.iter() {
+            if !palette.contains(color) {
+                palette.push(*color);
+            }
+        }
+    }
+    if palette.len() > 256 {
+        return Err(format!("{} colors don't fit a GIF palette", palette.len()));
+    }
+    // The color table holds 2^(bits) entries, and LZW codes start at 2 bits even for 2 colors
+    let bits = (1..=8).find(|b| palette.len() <= 1 << b).unwrap_or(1);
+    palette.resize(1 << bits, BLACK);
+
+    let mut bytes = b"GIF89a".to_vec();
+    bytes.extend(&(width as u16).to_le_bytes());
+    bytes.extend(&(height as u16).to_le_bytes());
+    // Global color table, background color and pixel aspect ratio
+    bytes.extend(&[0x80 | ((bits - 1) << 4) | (bits - 1), 0, 0]);
+    for Rgb(r, g, b) in &palette {
+        bytes.extend(&[*r, *g, *b]);
+    }
+    // Loop forever
+    bytes.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        bytes.extend(b"\x21\xf9\x04\x00");
+        bytes.extend(&delay.to_le_bytes());
+        bytes.extend(&[0, 0]);
+        bytes.push(0x2c);
+        bytes.extend(&[0, 0, 0, 0]);
+        bytes.extend(&(width as u16).to_le_bytes());
+        bytes.extend(&(height as u16).to_le_bytes());
+        bytes.push(0);
+
+        let min_code_size = bits.max(2);
+        let indices = frame
+            .iter()
+            .map(|(_, color)| palette.iter().position(|c| c == color).unwrap() as u8)
+            .collect::<Vec<_>>();
+        bytes.push(min_code_size);
+        for block in lzw(min_code_size, &indices).chunks(255) {
+            bytes.push(block.len() as u8);
+            bytes.extend(block);
+        }
+        bytes.push(0);
+    }
+    bytes.push(0x3b);
+    Ok(bytes)
+}
+
+// Variable width codes packed least significant bit first, as GIF wants them
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: u32,
+    len: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.bits |= (code as u32) << self.len;
+        self.len += width;
+        while self.len >= 8 {
+            self.bytes.push(self.bits as u8);
+            self.bits >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.bits as u8);
+        }
+        self.bytes
+    }
+}
+
+// The decoder learns each code one step after the encoder, and reads wider codes once
+// it has as many entries as the current width can tell apart
+fn widen(next: u16, width: &mut u8) {
+    if next == 1 << *width && *width < 12 {
+        *width += 1;
+    }
+}
+
+fn lzw(min_code_size: u8, indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut codes = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_code_size + 1;
+    let mut out = BitWriter {
+        bytes: vec![],
+        bits: 0,
+        len: 0,
+    };
+
+    out.write(clear, width);
+    let mut prefix = None;
+    for &index in indices {
+        let p = match prefix {
+            None => {
+                prefix = Some(index as u16);
+                continue;
+            }
+            Some(p) => p,
+        };
+        if let Some(&code) = codes.get(&(p, index)) {
+            prefix = Some(code);
+            continue;
+        }
+        out.write(p, width);
+        widen(next, &mut width);
+        if next == 4096 {
+            // The table is full, start over
+            out.write(clear, width);
+            codes.clear();
+            next = end + 1;
+            width = min_code_size + 1;
+        } else {
+            codes.insert((p, index), next);
+            next += 1;
+        }
+        prefix = Some(index as u16);
+    }
+    if let Some(p) = prefix {
+        out.write(p, width);
+        widen(next, &mut width);
+    }
+    out.write(end, width);
+    out.finish()
+}
+
+#[cfg(test)]
+fn lzw_decode(min_code_size: u8, bytes: &[u8]) -> Vec<u8> {
+    let clear = 1 << min_code_size;
+    let mut table: Vec<Vec<u8>> = vec![];
+    let mut width = min_code_size + 1;
+    let mut prev: Option<Vec<u8>> = None;
+    let mut indices = vec![];
+    let (mut bits, mut len, mut bytes) = (0u32, 0, bytes.iter());
+    loop {
+        while len < width {
+            bits |= (*bytes.next().unwrap() as u32) << len;
+            len += 8;
+        }
+        let code = (bits & ((1 << width) - 1)) as usize;
+        bits >>= width;
+        len -= width;
+        if code == clear {
+            table = (0..clear).map(|i| vec![i as u8]).collect();
+            // Room for the clear and end codes
+            table.extend(vec![vec![], vec![]]);
+            width = min_code_size + 1;
+            prev = None;
+            continue;
+        }
+        if code == clear + 1 {
+            return indices;
+        }
+        let entry = match (table.get(code), &prev) {
+            (Some(entry), _) => entry.clone(),
+            // The code being defined right now
+            (None, Some(prev)) => [prev.clone(), vec![prev[0]]].concat(),
+            (None, None) => panic!("unknown code {}", code),
+        };
+        if let Some(prev) = prev {
+            table.push([prev, vec![entry[0]]].concat());
+            if table.len() == 1 << width && width < 12 {
+                width += 1;
+            }
+        }
+        indices.extend(&entry);
+        prev = Some(entry);
+    }
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+}
+
+#[test]
+fn test_encode() {
+    let image = Grid::from_vec(3, vec![BLACK, WHITE, Rgb(255, 0, 0), WHITE, BLACK, BLACK]);
+    assert_eq!(encode(&image, Format::Pbm), b"P4\n3 2\n\xa0\x60".to_vec());
+    assert_eq!(
+        encode(&image, Format::Pgm),
+        b"P5\n3 2\n255\n\x00\xff\x4c\xff\x00\x00".to_vec()
+    );
+    assert_eq!(
+        &encode(&image, Format::Ppm)[..14],
+        b"P6\n3 2\n255\n\x00\x00\x00"
+    );
+
+    let png = encode(&image, Format::Png);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    // IHDR: 3 by 2, 8 bit RGB
+    assert_eq!(&png[16..26], &[0, 0, 0, 3, 0, 0, 0, 2, 8, 2]);
+    assert_eq!(
+        &png[png.len() - 12..],
+        b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"
+    );
+}
+
+#[test]
+fn test_scale() {
+    let image = Grid::from_vec(2, vec![BLACK, WHITE]);
+    let scaled = scale(&image, 2);
+    assert_eq!((scaled.width(), scaled.height()), (4, 2));
+    assert_eq!(scaled[Point::new(1, 1)], BLACK);
+    assert_eq!(scaled[Point::new(2, 0)], WHITE);
+}
+
+#[test]
+fn test_lzw() {
+    let mut seed = 1u32;
+    let noise = (0..20_000)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as u8 % 4
+        })
+        .collect::<Vec<_>>();
+    // Noise fills the code table and forces a restart, runs make long codes
+    for indices in &[noise, vec![1; 5000], vec![0, 1, 0, 1, 0, 1, 0], vec![]] {
+        assert_eq!(&lzw_decode(2, &lzw(2, indices)), indices);
+    }
+    let bytes = (0..=255).cycle().take(3000).collect::<Vec<_>>();
+    assert_eq!(lzw_decode(8, &lzw(8, &bytes)), bytes);
+}
+
+#[test]
+fn test_gif() {
+    let frames = vec![
+        Grid::from_vec(2, vec![BLACK, WHITE]),
+        Grid::from_vec(2, vec![WHITE, Rgb(255, 0, 0)]),
+    ];
+    let bytes = gif(&frames, 50).unwrap();
+    assert_eq!(&bytes[..10], b"GIF89a\x02\x00\x01\x00");
+    // A 4 color global table: black, white, red and padding
+    assert_eq!(
+        &bytes[13..25],
+        &[0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 0]
+    );
+    // One graphic control extension per frame
+    assert_eq!(bytes.windows(3).filter(|w| w == b"\x21\xf9\x04").count(), 2);
+    assert_eq!(bytes.last(), Some(&0x3b));
+
+    let colors = (0..=256)
+        .map(|i| Rgb((i % 256) as u8, (i / 256) as u8, 0))
+        .collect();
+    assert!(gif(&[Grid::from_vec(257, colors)], 10).is_err());
+}
@@ -0,0 +1,130 @@
+use crate::fetch::Fetcher;
+use crate::solution::Solution;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+// Directory with `day<N>/input` files, used when `--input-dir` isn't given
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+// Where downloaded inputs go when no input directory is given
+pub const DEFAULT_CACHE_DIR: &str = "inputs";
+
+// Where a day's input comes from, first match wins:
+// `--input <path>` (`-` for stdin), `--input-dir <dir>` or `$AOC_INPUT_DIR`,
+// a download into that directory if a fetcher is set, then the input bundled with the repo.
+// A failed download falls back to the bundled input with a warning.
+pub struct InputProvider {
+    file: Option<String>,
+    dir: Option<PathBuf>,
+    fetcher: Option<Fetcher>,
+}
+
+impl InputProvider {
+    pub fn new(file: Option<String>, dir: Option<String>) -> Self {
+        InputProvider {
+            file,
+            dir: dir.map(PathBuf::from),
+            fetcher: None,
+        }
+    }
+
+    // Download inputs missing from the input directory
+    pub fn fetch_missing(&mut self, fetcher: Fetcher) {
+        self.fetcher = Some(fetcher);
+    }
+
+    pub fn day_path(dir: &Path, day: u8) -> PathBuf {
+        dir.join(format!("day{}", day)).join("input")
+    }
+
+    pub fn load(&self, solution: &dyn Solution) -> Result<String, String> {
+        match (&self.file, &self.dir) {
+            (Some(path), _) if path == "-" => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("Can't read stdin: {}", e))?;
+                Ok(input)
+            }
+            (Some(path), _) => read(Path::new(path)),
+            (None, Some(dir)) => {
+                let path = InputProvider::day_path(dir, solution.day());
+                match &self.fetcher {
+                    _ if path.exists() => read(&path),
+                    Some(fetcher) => fetcher.fetch(solution.day()).or_else(|e| {
+                        eprintln!(
+                            "warning: can't download day {} ({}), using the bundled input",
+                            solution.day(),
+                            e
+                        );
+                        Ok(solution.input().to_string())
+                    }),
+                    None => Ok(solution.input().to_string()),
+                }
+            }
+            (None, None) => Ok(solution.input().to_string()),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))
+}
+
+#[test]
+fn test_load() {
+    use crate::day6::Day6;
+
+    let dir = std::env::temp_dir().join(format!("aoc-2019-input-{}", std::process::id()));
+    fs::create_dir_all(dir.join("day6")).unwrap();
+    fs::write(InputProvider::day_path(&dir, 6), "COM)A").unwrap();
+    let dir_name = Some(dir.to_string_lossy().to_string());
+
+    assert_eq!(
+        InputProvider::new(None, None).load(&Day6),
+        Ok(Day6.input().to_string())
+    );
+    assert_eq!(
+        InputProvider::new(None, dir_name.clone()).load(&Day6),
+        Ok("COM)A".to_string())
+    );
+    // Falls back to the bundled input for days missing in the directory
+    assert_eq!(
+        InputProvider::new(None, dir_name.clone()).load(&crate::day3::Day3),
+        Ok(crate::day3::Day3.input().to_string())
+    );
+    let file = InputProvider::day_path(&dir, 6)
+        .to_string_lossy()
+        .to_string();
+    assert_eq!(
+        InputProvider::new(Some(file), None).load(&Day6),
+        Ok("COM)A".to_string())
+    );
+    assert!(
+        InputProvider::new(Some("/nonexistent".to_string()), dir_name.clone())
+            .load(&Day6)
+            .is_err()
+    );
+
+    // A download that fails falls back to the bundled input
+    struct Offline;
+    impl crate::fetch::HttpBackend for Offline {
+        fn get(&self, _: &str, _: &[(&str, String)]) -> Result<String, String> {
+            Err("offline".to_string())
+        }
+    }
+    let mut inputs = InputProvider::new(None, dir_name);
+    inputs.fetch_missing(Fetcher::new(
+        Box::new(Offline),
+        "https://example.com",
+        "token",
+        dir.clone(),
+    ));
+    assert_eq!(
+        inputs.load(&crate::day3::Day3),
+        Ok(crate::day3::Day3.input().to_string())
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,284 @@
+use super::{decode, Instruction, IntcodeProgram, Parameter};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock {
+    pub start: usize,
+    // Address right after the last instruction
+    pub end: usize,
+    pub successors: Vec<usize>,
+    // Ends with a jump whose target is only known at runtime
+    pub indirect_jump: bool,
+}
+
+// Static view of a program: only immediate jump targets are followed from address 0.
+// Relative mode writes can't be resolved statically, so only position mode writes
+// into decoded code are reported as self-modifying.
+pub struct ControlFlowGraph {
+    pub blocks: BTreeMap<usize, BasicBlock>,
+    // Reachable addresses which don't hold a valid instruction
+    pub invalid: BTreeSet<usize>,
+    // (address of the writing instruction, address written to)
+    pub self_modifying_writes: Vec<(usize, usize)>,
+    instructions: BTreeMap<usize, Instruction>,
+}
+
+fn instruction_size(value: isize) -> Option<usize> {
+    match value % 100 {
+        1 | 2 | 7 | 8 => Some(4),
+        5 | 6 => Some(3),
+        3 | 4 | 9 => Some(2),
+        99 => Some(1),
+        _ => None,
+    }
+}
+
+fn is_valid(program: &IntcodeProgram, ip: usize) -> bool {
+    match program.get(ip) {
+        Some(value) if *value >= 0 => match instruction_size(*value) {
+            Some(size) => {
+                ip + size <= program.len()
+                    && (1..size).all(|i| value / 10_isize.pow(i as u32 + 1) % 10 <= 2)
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+// Whether execution may continue with the next instruction, and the jump target if any.
+fn flow(instruction: &Instruction) -> (bool, Option<Parameter>) {
+    match instruction {
+        Instruction::Halt => (false, None),
+        Instruction::JumpIfTrue(condition, target)
+        | Instruction::JumpIfFalse(condition, target) => {
+            let jump_if = matches!(instruction, Instruction::JumpIfTrue(..));
+            match condition {
+                Parameter::Immediate(value) if (*value != 0) == jump_if => (false, Some(*target)),
+                Parameter::Immediate(_) => (true, None),
+                _ => (true, Some(*target)),
+            }
+        }
+        _ => (true, None),
+    }
+}
+
+impl ControlFlowGraph {
+    pub fn new(program: &IntcodeProgram) -> Self {
+        let mut instructions = BTreeMap::new();
+        let mut invalid = BTreeSet::new();
+        let mut leaders = BTreeSet::new();
+        let mut stack = vec![0];
+        leaders.insert(0);
+
+        while let Some(ip) = stack.pop() {
+            if instructions.contains_key(&ip) || invalid.contains(&ip) {
+                continue;
+            }
+            if !is_valid(program, ip) {
+                invalid.insert(ip);
+                continue;
+            }
+            let instruction = decode(program, ip);
+            let next = ip + instruction.size();
+            instructions.insert(ip, instruction);
+
+            let (fallthrough, target) = flow(&instruction);
+            if fallthrough {
+                stack.push(next);
+            }
+            if let Some(target) = target {
+                leaders.insert(next);
+                if let Parameter::Immediate(target) = target {
+                    if target >= 0 {
+                        leaders.insert(target as usize);
+                        stack.push(target as usize);
+                    }
+                }
+            }
+        }
+
+        let mut blocks = BTreeMap::new();
+        let mut current: Option<BasicBlock> = None;
+        for (ip, instruction) in &instructions {
+            let block = match current.take() {
+                Some(block) if block.end == *ip && !leaders.contains(ip) => block,
+                Some(block) => {
+                    blocks.insert(block.start, block);
+                    BasicBlock::starting_at(*ip)
+                }
+                None => BasicBlock::starting_at(*ip),
+            };
+            let block = block.push(instruction);
+            if block.is_closed() {
+                blocks.insert(block.start, block);
+            } else {
+                current = Some(block);
+            }
+        }
+        if let Some(block) = current {
+            blocks.insert(block.start, block);
+        }
+
+        let mut self_modifying_writes = vec![];
+        for (ip, instruction) in &instructions {
+            if let Some(Parameter::Position(address)) = instruction.output() {
+                let is_code = instructions
+                    .range(..=*address)
+                    .next_back()
+                    .is_some_and(|(start, code)| *address < start + code.size());
+                if is_code {
+                    self_modifying_writes.push((*ip, *address));
+                }
+            }
+        }
+
+        ControlFlowGraph {
+            blocks,
+            invalid,
+            self_modifying_writes,
+            instructions,
+        }
+    }
+
+    fn block_containing(&self, address: usize) -> Option<&BasicBlock> {
+        self.blocks
+            .range(..=address)
+            .next_back()
+            .map(|(_, block)| block)
+            .filter(|block| address < block.end)
+    }
+
+    // Graphviz DOT, e.g. `dot -Tsvg cfg.dot > cfg.svg`
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph intcode {{").unwrap();
+        writeln!(dot, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+        for block in self.blocks.values() {
+            let label = self
+                .instructions
+                .range(block.start..block.end)
+                .map(|(ip, instruction)| format!("{}: {}\\l", ip, instruction))
+                .collect::<String>();
+            let color = if block.indirect_jump {
+                ", color=red"
+            } else {
+                ""
+            };
+            writeln!(dot, "    b{} [label=\"{}\"{}];", block.start, label, color).unwrap();
+            for successor in &block.successors {
+                writeln!(dot, "    b{} -> b{};", block.start, successor).unwrap();
+            }
+        }
+        for address in &self.invalid {
+            writeln!(
+                dot,
+                "    b{} [label=\"{}: ???\", style=dashed];",
+                address, address
+            )
+            .unwrap();
+        }
+        for (ip, address) in &self.self_modifying_writes {
+            if let (Some(writer), Some(target)) =
+                (self.block_containing(*ip), self.block_containing(*address))
+            {
+                writeln!(
+                    dot,
+                    "    b{} -> b{} [style=dotted, color=red, label=\"{}: write {}\"];",
+                    writer.start, target.start, ip, address
+                )
+                .unwrap();
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+impl BasicBlock {
+    fn starting_at(start: usize) -> Self {
+        BasicBlock {
+            start,
+            end: start,
+            successors: vec![],
+            indirect_jump: false,
+        }
+    }
+
+    fn push(mut self, instruction: &Instruction) -> Self {
+        self.end += instruction.size();
+        let (fallthrough, target) = flow(instruction);
+        self.successors.clear();
+        self.indirect_jump = false;
+        match target {
+            Some(Parameter::Immediate(target)) if target >= 0 => {
+                self.successors.push(target as usize)
+            }
+            Some(Parameter::Immediate(_)) => {}
+            Some(_) => self.indirect_jump = true,
+            None => {}
+        }
+        if fallthrough {
+            self.successors.push(self.end);
+        }
+        self
+    }
+
+    fn is_closed(&self) -> bool {
+        self.indirect_jump || self.successors != [self.end]
+    }
+}
+
+#[test]
+fn test_branch_blocks() {
+    // 0: in [9]; 2: jz [9], 8; 5: out 1; 7: halt; 8: halt
+    let program = vec![3, 9, 1006, 9, 8, 104, 1, 99, 99, 0];
+    let cfg = ControlFlowGraph::new(&program);
+    assert_eq!(
+        cfg.blocks.keys().copied().collect::<Vec<_>>(),
+        vec![0, 5, 8]
+    );
+    assert_eq!(cfg.blocks[&0].successors, vec![8, 5]);
+    assert!(cfg.blocks[&5].successors.is_empty());
+    assert!(cfg.blocks[&8].successors.is_empty());
+    assert!(cfg.invalid.is_empty());
+    assert!(cfg.self_modifying_writes.is_empty());
+}
+
+#[test]
+fn test_indirect_jump_and_self_modifying_write() {
+    // 0: in [3]; 2: out 0 (operand patched by the input); 4: jnz 1, [10]
+    let program = vec![3, 3, 104, 0, 105, 1, 10, 99, 99, 99, 7];
+    let cfg = ControlFlowGraph::new(&program);
+    let block = &cfg.blocks[&0];
+    assert!(block.indirect_jump);
+    assert_eq!(block.end, 7);
+    assert!(block.successors.is_empty());
+    assert_eq!(cfg.self_modifying_writes, vec![(0, 3)]);
+}
+
+#[test]
+fn test_unconditional_jump_and_invalid_target() {
+    // 0: jnz 1, 5; 3: (never reached data); 5: jz 0, 8; 8: garbage
+    let program = vec![1105, 1, 5, 42, 42, 1106, 0, 8, 42];
+    let cfg = ControlFlowGraph::new(&program);
+    assert_eq!(cfg.blocks[&0].successors, vec![5]);
+    assert_eq!(cfg.blocks[&5].successors, vec![8]);
+    assert_eq!(cfg.invalid.iter().copied().collect::<Vec<_>>(), vec![8]);
+}
+
+#[test]
+fn test_to_dot() {
+    let program = vec![3, 3, 104, 0, 99];
+    let cfg = ControlFlowGraph::new(&program);
+    assert_eq!(
+        cfg.to_dot(),
+        r#"digraph intcode {
+    node [shape=box, fontname="monospace"];
+    b0 [label="0: in [3]\l2: out 0\l4: halt\l"];
+    b0 -> b0 [style=dotted, color=red, label="0: write 3"];
+}
+"#
+    );
+}
@@ -0,0 +1,90 @@
+use super::{decode, Action, Instruction, IntcodeMemory, Machine};
+
+// Longest instruction: opcode + 3 parameters
+const MAX_INSTRUCTION_SIZE: usize = 4;
+
+// Same semantics as `Machine`, but every address is decoded only once. Writes into
+// memory drop the cached instructions covering the written address, so programs
+// patching their own code still behave.
+pub struct DecodedMachine {
+    machine: Machine,
+    cache: Vec<Option<Instruction>>,
+}
+
+impl DecodedMachine {
+    pub fn with_capacity(memory: &IntcodeMemory, capacity: usize) -> Self {
+        DecodedMachine::from(Machine::with_capacity(memory, capacity))
+    }
+
+    pub fn push_input(&mut self, input: isize) {
+        self.machine.push_input(input);
+    }
+
+    fn invalidate(&mut self, address: usize) {
+        let start = address.saturating_sub(MAX_INSTRUCTION_SIZE - 1);
+        let end = (address + 1).min(self.cache.len());
+        for cached in self.cache.iter_mut().take(end).skip(start) {
+            *cached = None;
+        }
+    }
+
+    pub fn step(&mut self) -> Option<Action> {
+        let ip = self.machine.ip;
+        let instruction = match self.cache[ip] {
+            Some(instruction) => instruction,
+            None => {
+                let instruction = decode(&self.machine.memory, ip);
+                self.cache[ip] = Some(instruction);
+                instruction
+            }
+        };
+        if let Some(address) = instruction.write_address(self.machine.relative_base) {
+            self.invalidate(address);
+        }
+        self.machine.ip += instruction.size();
+        self.machine.execute(instruction)
+    }
+
+    pub fn run(&mut self) -> Action {
+        loop {
+            let action = self.step();
+            if let Some(action) = action {
+                return action;
+            }
+        }
+    }
+}
+
+impl From<Machine> for DecodedMachine {
+    fn from(machine: Machine) -> Self {
+        let cache = vec![None; machine.memory.len()];
+        DecodedMachine { machine, cache }
+    }
+}
+
+#[test]
+fn test_same_outputs_as_machine() {
+    let program = vec![
+        109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+    ];
+    let mut machine = Machine::with_capacity(&program, 200);
+    let mut decoded = DecodedMachine::with_capacity(&program, 200);
+    loop {
+        let action = machine.run();
+        assert_eq!(decoded.run(), action);
+        if action == Action::Halt {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_self_modifying_write() {
+    // Input overwrites the immediate operand of the output instruction at 0,
+    // after that instruction has already been decoded and cached once.
+    let program = vec![104, 7, 1105, 1, 7, 99, 99, 3, 1, 1105, 1, 0];
+    let mut decoded = DecodedMachine::from(Machine::new(&program));
+    decoded.push_input(42);
+    assert_eq!(decoded.run(), Action::Output(7));
+    assert_eq!(decoded.run(), Action::Output(42));
+}
@@ -0,0 +1,104 @@
+use super::{Action, Machine};
+use std::fmt;
+
+// State of two machines stopped at the same output (or halt).
+#[derive(Debug, PartialEq)]
+pub struct MachineDiff {
+    pub actions: (Action, Action),
+    pub ip: (usize, usize),
+    pub relative_base: (isize, isize),
+    // (address, left value, right value) of every cell that differs
+    pub memory: Vec<(usize, isize, isize)>,
+}
+
+impl MachineDiff {
+    fn new(left: &Machine, right: &Machine, actions: (Action, Action)) -> Self {
+        let len = left.memory.len().max(right.memory.len());
+        let cell = |machine: &Machine, address| *machine.memory.get(address).unwrap_or(&0);
+        MachineDiff {
+            actions,
+            ip: (left.ip, right.ip),
+            relative_base: (left.relative_base, right.relative_base),
+            memory: (0..len)
+                .map(|address| (address, cell(left, address), cell(right, address)))
+                .filter(|(_, l, r)| l != r)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for MachineDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "action: {:?} | {:?}", self.actions.0, self.actions.1)?;
+        writeln!(f, "ip: {} | {}", self.ip.0, self.ip.1)?;
+        writeln!(
+            f,
+            "relative_base: {} | {}",
+            self.relative_base.0, self.relative_base.1
+        )?;
+        for (address, l, r) in &self.memory {
+            writeln!(f, "[{}]: {} | {}", address, l, r)?;
+        }
+        Ok(())
+    }
+}
+
+// Run both machines output by output until both halted, diffing them at every stop.
+// A machine that halted first stays as it is while the other one keeps running.
+pub fn diff_runs(left: &mut Machine, right: &mut Machine) -> Vec<MachineDiff> {
+    let mut diffs = vec![];
+    let mut actions = (Action::Output(0), Action::Output(0));
+    while actions != (Action::Halt, Action::Halt) {
+        if actions.0 != Action::Halt {
+            actions.0 = left.run();
+        }
+        if actions.1 != Action::Halt {
+            actions.1 = right.run();
+        }
+        diffs.push(MachineDiff::new(left, right, actions));
+    }
+    diffs
+}
+
+#[test]
+fn test_diff_runs() {
+    // Output input * 2 and halt
+    let program = vec![3, 9, 1002, 9, 2, 9, 4, 9, 99, 0];
+    let mut left = Machine::new(&program);
+    let mut right = Machine::new(&program);
+    left.push_input(3);
+    right.push_input(5);
+    let diffs = diff_runs(&mut left, &mut right);
+    assert_eq!(
+        diffs,
+        vec![
+            MachineDiff {
+                actions: (Action::Output(6), Action::Output(10)),
+                ip: (8, 8),
+                relative_base: (0, 0),
+                memory: vec![(9, 6, 10)],
+            },
+            MachineDiff {
+                actions: (Action::Halt, Action::Halt),
+                ip: (9, 9),
+                relative_base: (0, 0),
+                memory: vec![(9, 6, 10)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_runs_halting_at_different_times() {
+    // Output 1 if input is 0, then halt
+    let program = vec![3, 9, 1005, 9, 7, 104, 1, 99, 0, 0];
+    let mut left = Machine::new(&program);
+    let mut right = Machine::new(&program);
+    left.push_input(0);
+    right.push_input(1);
+    let diffs = diff_runs(&mut left, &mut right);
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[0].actions, (Action::Output(1), Action::Halt));
+    assert_eq!(diffs[1].actions, (Action::Halt, Action::Halt));
+    assert_eq!(diffs[1].memory, vec![(9, 0, 1)]);
+}
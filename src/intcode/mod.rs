@@ -1,15 +1,30 @@
 // https://github.com/michaelmelanson/advent-of-code-2019/blob/master/src/intcode/mod.rs
+mod cfg;
+mod decoded;
+mod diff;
+mod self_modifying;
+
+use crate::parse::ParseError;
+pub use cfg::ControlFlowGraph;
+pub use decoded::DecodedMachine;
+pub use diff::{diff_runs, MachineDiff};
+pub use self_modifying::SelfModifyingLog;
+use std::fmt;
+
 pub type IntcodeProgram = Vec<isize>;
 type IntcodeMemory = Vec<isize>;
 
-pub fn intcode_parser(input: &str) -> IntcodeProgram {
+pub fn intcode_parser(input: &str) -> Result<IntcodeProgram, ParseError> {
     input
         .split(',')
-        .map(|s| s.parse::<isize>().expect("Invalid intcode"))
+        .map(|s| {
+            s.parse::<isize>()
+                .map_err(|_| ParseError::at(input, s, format!("invalid intcode {:?}", s)))
+        })
         .collect()
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Parameter {
     Position(usize),
     Immediate(isize),
@@ -35,7 +50,17 @@ impl Parameter {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parameter::Position(position) => write!(f, "[{}]", position),
+            Parameter::Immediate(value) => write!(f, "{}", value),
+            Parameter::Relative(value) => write!(f, "[rb{:+}]", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 enum Instruction {
     Add(Parameter, Parameter, Parameter),
     Multiply(Parameter, Parameter, Parameter),
@@ -49,7 +74,97 @@ enum Instruction {
     Halt,
 }
 
-#[derive(Debug, PartialEq)]
+impl Instruction {
+    fn size(&self) -> usize {
+        match self {
+            Instruction::Add(..)
+            | Instruction::Multiply(..)
+            | Instruction::LessThan(..)
+            | Instruction::Equals(..) => 4,
+            Instruction::JumpIfTrue(..) | Instruction::JumpIfFalse(..) => 3,
+            Instruction::Input(_) | Instruction::Output(_) | Instruction::SetRelativeBase(_) => 2,
+            Instruction::Halt => 1,
+        }
+    }
+
+    fn output(&self) -> Option<&Parameter> {
+        match self {
+            Instruction::Add(_, _, output)
+            | Instruction::Multiply(_, _, output)
+            | Instruction::LessThan(_, _, output)
+            | Instruction::Equals(_, _, output)
+            | Instruction::Input(output) => Some(output),
+            _ => None,
+        }
+    }
+
+    // Address this instruction is going to write to, if any.
+    fn write_address(&self, relative_base: isize) -> Option<usize> {
+        match self.output()? {
+            Parameter::Position(position) => Some(*position),
+            Parameter::Relative(value) => Some((*value + relative_base) as usize),
+            Parameter::Immediate(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Add(a, b, c) => write!(f, "add {}, {}, {}", a, b, c),
+            Instruction::Multiply(a, b, c) => write!(f, "mul {}, {}, {}", a, b, c),
+            Instruction::Input(a) => write!(f, "in {}", a),
+            Instruction::Output(a) => write!(f, "out {}", a),
+            Instruction::JumpIfTrue(a, b) => write!(f, "jnz {}, {}", a, b),
+            Instruction::JumpIfFalse(a, b) => write!(f, "jz {}, {}", a, b),
+            Instruction::LessThan(a, b, c) => write!(f, "lt {}, {}, {}", a, b, c),
+            Instruction::Equals(a, b, c) => write!(f, "eq {}, {}, {}", a, b, c),
+            Instruction::SetRelativeBase(a) => write!(f, "arb {}", a),
+            Instruction::Halt => write!(f, "halt"),
+        }
+    }
+}
+
+fn decode(memory: &IntcodeMemory, ip: usize) -> Instruction {
+    let instruction_value = memory[ip];
+    let opcode = instruction_value % 100;
+    let first_mode = instruction_value / 100 % 10;
+    let second_mode = instruction_value / 1000 % 10;
+    let third_mode = instruction_value / 10000 % 10;
+    let param = |offset: usize, mode: isize| Parameter::new(mode, memory[ip + offset]);
+
+    match opcode {
+        1 => Instruction::Add(
+            param(1, first_mode),
+            param(2, second_mode),
+            param(3, third_mode),
+        ),
+        2 => Instruction::Multiply(
+            param(1, first_mode),
+            param(2, second_mode),
+            param(3, third_mode),
+        ),
+        3 => Instruction::Input(param(1, first_mode)),
+        4 => Instruction::Output(param(1, first_mode)),
+        5 => Instruction::JumpIfTrue(param(1, first_mode), param(2, second_mode)),
+        6 => Instruction::JumpIfFalse(param(1, first_mode), param(2, second_mode)),
+        7 => Instruction::LessThan(
+            param(1, first_mode),
+            param(2, second_mode),
+            param(3, third_mode),
+        ),
+        8 => Instruction::Equals(
+            param(1, first_mode),
+            param(2, second_mode),
+            param(3, third_mode),
+        ),
+        9 => Instruction::SetRelativeBase(param(1, first_mode)),
+        99 => Instruction::Halt,
+        _ => unimplemented!(),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Action {
     Output(isize),
     Halt,
@@ -60,6 +175,7 @@ pub struct Machine {
     ip: usize,
     inputs: Vec<isize>,
     relative_base: isize,
+    self_modifying: Option<SelfModifyingLog>,
 }
 
 impl Machine {
@@ -69,6 +185,7 @@ impl Machine {
             ip: 0,
             inputs: vec![],
             relative_base: 0,
+            self_modifying: None,
         }
     }
 
@@ -80,13 +197,17 @@ impl Machine {
             ip: 0,
             inputs: vec![],
             relative_base: 0,
+            self_modifying: None,
         }
     }
 
-    fn read(&mut self) -> isize {
-        let value = self.memory[self.ip];
-        self.ip += 1;
-        value
+    // Record every write into code that has already been executed.
+    pub fn track_self_modifying(&mut self) {
+        self.self_modifying = Some(SelfModifyingLog::default());
+    }
+
+    pub fn into_self_modifying(self) -> Option<SelfModifyingLog> {
+        self.self_modifying
     }
 
     fn jump(&mut self, address: usize) {
@@ -116,52 +237,22 @@ impl Machine {
     }
 
     fn next_instruction(&mut self) -> Instruction {
-        let instruction_value = self.read();
-        let opcode = instruction_value % 100;
-        let first_mode = instruction_value / 100 % 10;
-        let second_mode = instruction_value / 1000 % 10;
-        let third_mode = instruction_value / 10000 % 10;
-
-        match opcode {
-            1 => Instruction::Add(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-                Parameter::new(third_mode, self.read()),
-            ),
-            2 => Instruction::Multiply(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-                Parameter::new(third_mode, self.read()),
-            ),
-            3 => Instruction::Input(Parameter::new(first_mode, self.read())),
-            4 => Instruction::Output(Parameter::new(first_mode, self.read())),
-            5 => Instruction::JumpIfTrue(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-            ),
-            6 => Instruction::JumpIfFalse(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-            ),
-            7 => Instruction::LessThan(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-                Parameter::new(third_mode, self.read()),
-            ),
-            8 => Instruction::Equals(
-                Parameter::new(first_mode, self.read()),
-                Parameter::new(second_mode, self.read()),
-                Parameter::new(third_mode, self.read()),
-            ),
-            9 => Instruction::SetRelativeBase(Parameter::new(first_mode, self.read())),
-            99 => Instruction::Halt,
-            _ => unimplemented!(),
-        }
+        let instruction = decode(&self.memory, self.ip);
+        self.ip += instruction.size();
+        instruction
     }
 
     pub fn step(&mut self) -> Option<Action> {
-        let mut action = None;
+        let ip = self.ip;
         let instruction = self.next_instruction();
+        if let Some(log) = self.self_modifying.as_mut() {
+            log.record(ip, &instruction, self.relative_base);
+        }
+        self.execute(instruction)
+    }
+
+    fn execute(&mut self, instruction: Instruction) -> Option<Action> {
+        let mut action = None;
         match instruction {
             Instruction::Add(lhs, rhs, output) => {
                 let lhs = lhs.resolve(&self.memory, self.relative_base);
@@ -235,6 +326,13 @@ impl Machine {
     }
 }
 
+#[test]
+fn test_intcode_parser() {
+    assert_eq!(intcode_parser("1,-2,99"), Ok(vec![1, -2, 99]));
+    let e = intcode_parser("1,2,x3,99").unwrap_err();
+    assert_eq!((e.line, e.column), (1, 5));
+}
+
 #[test]
 fn test_relative_mode() {
     let mut program = vec![
@@ -258,3 +356,20 @@ fn test_init_with_capacity() {
     let machine = Machine::with_capacity(&program, 5);
     assert_eq!(machine.memory, vec![1, 2, 3, 0, 0]);
 }
+
+#[test]
+fn test_track_self_modifying() {
+    // 0: add 0, 1, [5] patches the operand of `out` at 4, which hasn't run yet;
+    // 6: add [5], 1, [5] patches it again after it ran; 10: jnz 1, 4
+    let program = vec![1101, 0, 1, 5, 104, 0, 1001, 5, 1, 5, 1105, 1, 4];
+    let mut machine = Machine::new(&program);
+    machine.track_self_modifying();
+    assert_eq!(machine.run(), Action::Output(1));
+    assert_eq!(machine.run(), Action::Output(2));
+    let log = machine.into_self_modifying().unwrap();
+    assert_eq!(
+        log.writes(),
+        &[self_modifying::SelfModifyingWrite { ip: 6, address: 5 }]
+    );
+    assert!(!log.is_cache_safe());
+}
@@ -0,0 +1,38 @@
+use super::Instruction;
+use std::collections::HashSet;
+
+#[derive(Debug, PartialEq)]
+pub struct SelfModifyingWrite {
+    // Address of the writing instruction
+    pub ip: usize,
+    // Previously executed address that got overwritten
+    pub address: usize,
+}
+
+#[derive(Default)]
+pub struct SelfModifyingLog {
+    executed: HashSet<usize>,
+    writes: Vec<SelfModifyingWrite>,
+}
+
+impl SelfModifyingLog {
+    pub(super) fn record(&mut self, ip: usize, instruction: &Instruction, relative_base: isize) {
+        // Operands are part of the executed code too
+        self.executed.extend(ip..ip + instruction.size());
+        if let Some(address) = instruction.write_address(relative_base) {
+            if self.executed.contains(&address) {
+                self.writes.push(SelfModifyingWrite { ip, address });
+            }
+        }
+    }
+
+    pub fn writes(&self) -> &[SelfModifyingWrite] {
+        &self.writes
+    }
+
+    // A cache of decoded instructions which is never invalidated only stays correct
+    // as long as nothing that already ran gets patched.
+    pub fn is_cache_safe(&self) -> bool {
+        self.writes.is_empty()
+    }
+}
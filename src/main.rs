@@ -1,5 +1,33 @@
-use std::env;
+use cli::Command;
+use grid::Point;
+use input::InputProvider;
+use solution::Solution;
+use std::path::Path;
+use std::{env, fs, process};
 
+// Runs every puzzle example in `fixtures/day<N>` against a day, e.g. `fixture_tests!(Day6);`
+// Defined ahead of the day modules so they can all use it.
+macro_rules! fixture_tests {
+    ($solution:expr) => {
+        #[test]
+        fn test_fixtures() {
+            crate::fixtures::check(&$solution);
+        }
+    };
+}
+
+// Checks a day's parts on its input against answers.toml, e.g. `answer_tests!(Day6);`
+macro_rules! answer_tests {
+    ($solution:expr) => {
+        #[test]
+        fn test_answers() {
+            crate::verify::check_answers(&$solution);
+        }
+    };
+}
+
+mod bench;
+mod cli;
 mod day1;
 mod day10;
 mod day11;
@@ -13,35 +41,405 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod fetch;
+#[cfg(test)]
+mod fixtures;
+mod grid;
+mod image;
+mod input;
 mod intcode;
+mod ocr;
+mod parse;
+mod solution;
+mod verify;
+
+fn run(day: u8, part: Option<u8>, inputs: &InputProvider) -> Result<(), String> {
+    let solution = solution::find(day).ok_or(format!("No solution for day {}", day))?;
+    let input = inputs.load(solution.as_ref())?;
+    match part {
+        Some(1) => println!("{}", solution.part1(&input)?),
+        Some(_) => println!("{}", solution.part2(&input)?),
+        None => {
+            println!("Part 1: {}", solution.part1(&input)?);
+            println!("Part 2: {}", solution.part2(&input)?);
+        }
+    }
+    Ok(())
+}
+
+fn run_all(inputs: &InputProvider) -> Result<(), String> {
+    let mut failures = vec![];
+    println!("{:>3} | {:>16} | {:>16}", "day", "part 1", "part 2");
+    for solution in solution::registry() {
+        let input = inputs.load(solution.as_ref())?;
+        let mut cell = |part| match solution::run_part(solution.as_ref(), part, &input) {
+            Ok(answer) => answer.summary(),
+            Err(e) => {
+                failures.push(format!("day {} part {}: {}", solution.day(), part, e));
+                "failed".to_string()
+            }
+        };
+        let part1 = cell(1);
+        let part2 = cell(2);
+        println!("{:>3} | {:>16} | {:>16}", solution.day(), part1, part2);
+    }
+
+    for failure in &failures {
+        eprintln!("{}", failure);
+    }
+    match failures.len() {
+        0 => Ok(()),
+        n => Err(format!("{} part(s) failed", n)),
+    }
+}
+
+fn bench(
+    day: Option<u8>,
+    runs: usize,
+    output: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+    inputs: &InputProvider,
+) -> Result<(), String> {
+    let solutions = match day {
+        Some(day) => vec![solution::find(day).ok_or(format!("No solution for day {}", day))?],
+        None => solution::registry(),
+    };
+
+    println!(
+        "{:>3} | {:>4} | {:>12} | {:>12} | {:>12}",
+        "day", "part", "min", "median", "max"
+    );
+    let mut timings = vec![];
+    for solution in solutions {
+        let input = inputs.load(solution.as_ref())?;
+        for t in bench::bench(solution.as_ref(), &input, runs)? {
+            println!(
+                "{:>3} | {:>4} | {:>12} | {:>12} | {:>12}",
+                t.day,
+                t.part,
+                format!("{:.2?}", t.min),
+                format!("{:.2?}", t.median),
+                format!("{:.2?}", t.max)
+            );
+            timings.push(t);
+        }
+    }
+
+    if let Some(path) = output {
+        let report = if path.ends_with(".json") {
+            bench::to_json(&timings)
+        } else {
+            bench::to_csv(&timings)
+        };
+        fs::write(&path, report).map_err(|e| format!("Can't write {}: {}", path, e))?;
+    }
+
+    if let Some(path) = baseline {
+        let report =
+            fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+        let baseline = bench::parse_report(&report).map_err(|e| format!("{}: {}", path, e))?;
+        let regressions = bench::compare(&timings, &baseline, threshold);
+        for r in &regressions {
+            println!(
+                "Regression: day {} part {} median {:.2?} -> {:.2?}",
+                r.day, r.part, r.baseline, r.current
+            );
+        }
+        if !regressions.is_empty() {
+            return Err(format!("{} regression(s)", regressions.len()));
+        }
+    }
+    Ok(())
+}
+
+fn verify(answers: Option<String>, inputs: &InputProvider) -> Result<(), String> {
+    let answers = match answers {
+        Some(path) => {
+            let toml =
+                fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+            verify::parse_answers(&toml).map_err(|e| format!("{}: {}", path, e))?
+        }
+        None => verify::parse_answers(verify::ANSWERS)?,
+    };
+    let checks = verify::verify(inputs, &answers)?;
+    print!("{}", verify::table(&checks));
+    match checks.iter().filter(|c| verify::is_failure(c)).count() {
+        0 => Ok(()),
+        n => Err(format!("{} part(s) failed verification", n)),
+    }
+}
+
+fn image(
+    day: u8,
+    path: &str,
+    scale: usize,
+    palette: Option<String>,
+    inputs: &InputProvider,
+) -> Result<(), String> {
+    let format = image::Format::from_path(Path::new(path)).ok_or(format!(
+        "Unknown image format {}, use .pbm, .pgm, .ppm or .png",
+        path
+    ))?;
+    let solution = solution::find(day).ok_or(format!("No solution for day {}", day))?;
+    let input = inputs.load(solution.as_ref())?;
+    let picture = match (day, palette) {
+        (8, Some(path)) => {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read palette {}: {}", path, e))?;
+            let palette = day8::Palette::parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+            day8::composite(&input, &palette)?
+        }
+        (8, None) => day8::image(&input)?,
+        (11, None) => day11::image(&input)?,
+        (11, Some(_)) => return Err("Only day 8 images take a palette".to_string()),
+        _ => return Err(format!("Day {} has no picture", day)),
+    };
+    let bytes = image::encode(&image::scale(&picture, scale), format);
+    fs::write(path, bytes).map_err(|e| format!("Can't write {}: {}", path, e))
+}
+
+// A .gif path gets one animated GIF, any other path becomes a directory of numbered PNGs
+fn animate(
+    day: u8,
+    path: &str,
+    scale: usize,
+    delay: u16,
+    inputs: &InputProvider,
+) -> Result<(), String> {
+    if day != 8 {
+        return Err(format!("Day {} has no layers to animate", day));
+    }
+    let input = inputs.load(&day8::Day8)?;
+    let frames = day8::frames(&input)?
+        .iter()
+        .map(|frame| image::scale(frame, scale))
+        .collect::<Vec<_>>();
+    let write = |path: &Path, bytes| {
+        fs::write(path, bytes).map_err(|e| format!("Can't write {}: {}", path.display(), e))
+    };
+    if Path::new(path).extension().is_some_and(|ext| ext == "gif") {
+        return write(Path::new(path), image::gif(&frames, delay)?);
+    }
+    fs::create_dir_all(path).map_err(|e| format!("Can't create {}: {}", path, e))?;
+    for (i, frame) in frames.iter().enumerate() {
+        let file = Path::new(path).join(format!("layer{:03}.png", i + 1));
+        write(&file, image::encode(frame, image::Format::Png))?;
+    }
+    Ok(())
+}
+
+fn station(
+    at: Option<(i32, i32)>,
+    targets: usize,
+    heat: bool,
+    inputs: &InputProvider,
+) -> Result<(), String> {
+    let input = inputs.load(&day10::Day10)?;
+    let map = day10::parse_map(&input)?;
+    if heat {
+        println!("{}", day10::render_heat_map(&day10::visibility(&map)));
+        return Ok(());
+    }
+
+    let points = day10::asteroids(&map);
+    let station = match at {
+        Some((x, y)) => {
+            let p = Point::new(x, y);
+            if !points.contains(&p) {
+                return Err(format!("No asteroid at {},{}", x, y));
+            }
+            p
+        }
+        None => {
+            let (p, _) = day10::max_count_detects(&points).ok_or("No asteroids")?;
+            p
+        }
+    };
+    let order = day10::vaporization_order(&points, station);
+    let targets = &order[..targets.min(order.len())];
+    println!("{}", day10::render_targets(&map, station, targets));
+    println!("Station at {},{}", station.x, station.y);
+    for (i, p) in targets.iter().enumerate() {
+        println!("{:>4}: {},{}", i + 1, p.x, p.y);
+    }
+    Ok(())
+}
+
+fn hull(
+    start_white: bool,
+    svg: Option<String>,
+    script: Option<String>,
+    ant: Option<usize>,
+    inputs: &InputProvider,
+) -> Result<(), String> {
+    let mut brain: Box<dyn day11::Brain> = match (script, ant) {
+        (Some(path), _) => {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read script {}: {}", path, e))?;
+            Box::new(day11::Script::parse(&text).map_err(|e| format!("{}: {}", path, e))?)
+        }
+        (None, Some(steps)) => Box::new(day11::LangtonsAnt::new(steps)),
+        (None, None) => Box::new(day11::Intcode::new(inputs.load(&day11::Day11)?.trim())?),
+    };
+    let start = if start_white {
+        day11::Color::White
+    } else {
+        day11::Color::Black
+    };
+    let painting = day11::paint_with(brain.as_mut(), start);
+    if let Some(e) = brain.error() {
+        eprintln!("warning: the robot stopped early, {}", e);
+    }
+    println!("{}", painting.render());
+    if let Ok(text) = painting.text() {
+        println!("Reads {}", text);
+    }
+    println!(
+        "{} panels painted, {} of them more than once, in {} steps",
+        painting.painted(),
+        painting.repainted(),
+        painting.steps
+    );
+    for (paints, panels) in painting.paint_histogram() {
+        println!("{:>6} panel(s) painted {} time(s)", panels, paints);
+    }
+    if let Some(path) = svg {
+        fs::write(&path, painting.svg(10)).map_err(|e| format!("Can't write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+fn input_provider(file: Option<String>, input_dir: Option<String>) -> InputProvider {
+    let input_dir = input_dir.or_else(|| env::var(input::INPUT_DIR_ENV).ok());
+    match env::var(fetch::SESSION_ENV) {
+        Ok(session) => {
+            let dir = input_dir.unwrap_or_else(|| input::DEFAULT_CACHE_DIR.to_string());
+            let base_url = env::var(fetch::BASE_URL_ENV).unwrap_or(fetch::BASE_URL.to_string());
+            let fetcher = fetch::Fetcher::new(
+                fetch::backend_for(&base_url),
+                &base_url,
+                &session,
+                dir.clone().into(),
+            );
+            let mut inputs = InputProvider::new(file, Some(dir));
+            inputs.fetch_missing(fetcher);
+            inputs
+        }
+        Err(_) => InputProvider::new(file, input_dir),
+    }
+}
+
+fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Run {
+            day,
+            part,
+            input,
+            input_dir,
+        } => run(day, part, &input_provider(input, input_dir))?,
+        Command::RunAll { input_dir } => run_all(&input_provider(None, input_dir))?,
+        Command::Bench {
+            day,
+            runs,
+            output,
+            baseline,
+            threshold,
+            input_dir,
+        } => bench(
+            day,
+            runs,
+            output,
+            baseline,
+            threshold,
+            &input_provider(None, input_dir),
+        )?,
+        Command::Verify { answers, input_dir } => {
+            verify(answers, &input_provider(None, input_dir))?
+        }
+        Command::Image {
+            day,
+            path,
+            scale,
+            palette,
+            input,
+            input_dir,
+        } => image(
+            day,
+            &path,
+            scale,
+            palette,
+            &input_provider(input, input_dir),
+        )?,
+        Command::Animate {
+            day,
+            path,
+            scale,
+            delay,
+            input,
+            input_dir,
+        } => animate(day, &path, scale, delay, &input_provider(input, input_dir))?,
+        Command::Station {
+            at,
+            targets,
+            heat,
+            input,
+            input_dir,
+        } => station(at, targets, heat, &input_provider(input, input_dir))?,
+        Command::Hull {
+            start_white,
+            svg,
+            script,
+            ant,
+            input,
+            input_dir,
+        } => hull(
+            start_white,
+            svg,
+            script,
+            ant,
+            &input_provider(input, input_dir),
+        )?,
+        Command::List => {
+            for solution in solution::registry() {
+                println!("day {}", solution.day());
+            }
+        }
+        // e.g. `cargo run cfg 9 | dot -Tsvg > day9.svg`
+        Command::Cfg(9) => print!("{}", day9::cfg()),
+        Command::Cfg(13) => print!("{}", day13::cfg()),
+        Command::Smc(day) => {
+            let log = match day {
+                9 => day9::self_modifying(),
+                13 => day13::self_modifying(),
+                _ => return Err(format!("No Intcode program for day {}", day)),
+            };
+            for write in log.writes() {
+                println!("ip {} wrote executed address {}", write.ip, write.address);
+            }
+            println!("Instruction cache safe: {}", log.is_cache_safe());
+        }
+        Command::Cfg(day) => return Err(format!("No Intcode program for day {}", day)),
+        Command::Diff => {
+            let (noun, verb) = day2::noun_verb_diffs(day2::Day2.input())?;
+            println!("noun 0 -> 1:\n{}", noun);
+            println!("verb 0 -> 1:\n{}", verb);
+        }
+    }
+    Ok(())
+}
 
 fn main() {
-    let solution = env::args().nth(1).unwrap_or_default();
-
-    match solution.parse::<usize>() {
-        Ok(1) => println!("day1 answer: {}", day1::answer()),
-        Ok(21) => println!("{:?}", day2::part_1()),
-        Ok(22) => println!("{:?}", day2::part_2()),
-        Ok(31) => println!("{:?}", day3::part_1()),
-        Ok(32) => println!("{:?}", day3::part_2()),
-        Ok(41) => println!("{:?}", day4::part_1()),
-        Ok(42) => println!("{:?}", day4::part_2()),
-        Ok(51) => println!("{:?}", day5::part_1()),
-        Ok(52) => println!("{:?}", day5::part_2()),
-        Ok(61) => println!("{:?}", day6::part_1()),
-        Ok(62) => println!("{:?}", day6::part_2()),
-        Ok(71) => println!("{:?}", day7::part_1()),
-        Ok(72) => println!("{:?}", day7::part_2()),
-        Ok(81) => println!("{:?}", day8::part_1()),
-        Ok(82) => println!("{}", day8::part_2()),
-        Ok(91) => println!("{:?}", day9::part_1()),
-        Ok(92) => println!("{:?}", day9::part_2()),
-        Ok(101) => println!("{:?}", day10::part_1()),
-        Ok(102) => println!("{:?}", day10::part_2()),
-        Ok(111) => println!("{:?}", day11::part_1()),
-        Ok(112) => day11::part_2(),
-        Ok(121) => println!("{:?}", day12::part_1()),
-        Ok(131) => println!("{:?}", day13::part_1()),
-        _ => panic!("Invalid pick"),
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = execute(command) {
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
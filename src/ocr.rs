@@ -0,0 +1,149 @@
+use crate::grid::{Grid, Point};
+use std::fmt;
+use std::ops::Range;
+
+// The letters that show up in puzzle answers, 6 pixels high and mostly 4 wide
+const FONT: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+#[derive(Debug, PartialEq)]
+pub struct UnknownGlyphs {
+    // Left column of every glyph that isn't in the font
+    pub columns: Vec<usize>,
+    // What was read, with `?` for the unknown glyphs
+    pub text: String,
+}
+
+impl fmt::Display for UnknownGlyphs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "unrecognized glyphs at columns {} (read {:?})",
+            columns.join(", "),
+            self.text
+        )
+    }
+}
+
+fn on(pixels: &Grid<bool>, x: usize, y: usize) -> bool {
+    pixels[Point::new(x as i32, y as i32)]
+}
+
+// Glyph as a string of rows, in the same form as `FONT`
+fn glyph(pixels: &Grid<bool>, columns: Range<usize>, rows: &[usize]) -> String {
+    rows.iter()
+        .map(|y| {
+            columns
+                .clone()
+                .map(|x| if on(pixels, x, *y) { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Font glyphs like I have empty columns, which would otherwise split them
+fn trim_columns(glyph: &str) -> String {
+    let rows = glyph.lines().collect::<Vec<_>>();
+    let lit = |x: usize| rows.iter().any(|row| row.as_bytes()[x] == b'#');
+    let width = rows[0].len();
+    let start = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let end = (0..width).rev().find(|x| lit(*x)).map_or(0, |x| x + 1);
+    rows.iter()
+        .map(|row| &row[start..end.max(start)])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Glyphs are told apart by the empty columns between them, empty rows above and below are ignored
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, UnknownGlyphs> {
+    let rows = (0..pixels.height())
+        .filter(|y| (0..pixels.width()).any(|x| on(pixels, x, *y)))
+        .collect::<Vec<_>>();
+    let lit = |x: usize| rows.iter().any(|y| on(pixels, x, *y));
+
+    let mut text = String::new();
+    let mut columns = vec![];
+    let mut x = 0;
+    while x < pixels.width() {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < pixels.width() && lit(x) {
+            x += 1;
+        }
+        let glyph = glyph(pixels, start..x, &rows);
+        match FONT.iter().find(|(_, g)| trim_columns(g) == glyph) {
+            Some((c, _)) => text.push(*c),
+            None => {
+                text.push('?');
+                columns.push(start);
+            }
+        }
+    }
+
+    if columns.is_empty() {
+        Ok(text)
+    } else {
+        Err(UnknownGlyphs { columns, text })
+    }
+}
+
+#[cfg(test)]
+fn render(text: &str) -> Grid<bool> {
+    let glyphs = text
+        .chars()
+        .map(|c| FONT.iter().find(|(f, _)| *f == c).unwrap().1)
+        .collect::<Vec<_>>();
+    let rows = (0..6)
+        .map(|y| {
+            glyphs
+                .iter()
+                .map(|g| format!("{}.", g.lines().nth(y).unwrap()))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    Grid::parse(&rows.join("\n"), |c| c == '#').unwrap()
+}
+
+#[test]
+fn test_recognize() {
+    let all = FONT.iter().map(|(c, _)| *c).collect::<String>();
+    assert_eq!(recognize(&render(&all)), Ok(all));
+
+    // A stray pixel on the L
+    let mut pixels = render("HLE");
+    pixels[Point::new(6, 0)] = true;
+    assert_eq!(
+        recognize(&pixels),
+        Err(UnknownGlyphs {
+            columns: vec![5],
+            text: "H?E".to_string()
+        })
+    );
+}
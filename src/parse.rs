@@ -0,0 +1,56 @@
+use std::fmt;
+
+// Where and why a puzzle input couldn't be parsed, line and column start at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    // Reports the error at the start of `part`, which must be a slice of `input`
+    pub fn at(input: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = part.as_ptr() as usize - input.as_ptr() as usize;
+        assert!(offset <= input.len(), "part isn't a slice of input");
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    // Reports the error just past the end of `input`, for inputs that stop early
+    pub fn at_end(input: &str, message: impl Into<String>) -> Self {
+        ParseError::at(input, &input[input.len()..], message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(e: ParseError) -> String {
+        format!("Invalid input, {}", e)
+    }
+}
+
+#[test]
+fn test_at() {
+    let input = "COM)B\nB)Ç\nC";
+    let error = |part: &str| ParseError::at(input, part, "oops");
+    assert_eq!((error(input).line, error(input).column), (1, 1));
+    assert_eq!((error(&input[8..]).line, error(&input[8..]).column), (2, 3));
+    let end = ParseError::at_end(input, "oops");
+    assert_eq!((end.line, end.column), (3, 2));
+    assert_eq!(end.to_string(), "line 3, column 2: oops");
+}
@@ -0,0 +1,128 @@
+use crate::parse::ParseError;
+use crate::{day1, day10, day11, day12, day13, day2, day3, day4, day5, day6, day7, day8, day9};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Number(i64),
+    // Multi-line renders like day 8's image are text answers too
+    Text(String),
+    // Part not solved yet, or the solver found nothing
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(v) => write!(f, "{}", v),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "-"),
+        }
+    }
+}
+
+impl Answer {
+    // Single line version for tables
+    pub fn summary(&self) -> String {
+        match self {
+            Answer::Text(s) if s.contains('\n') => "(image)".to_string(),
+            answer => answer.to_string(),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(v: usize) -> Self {
+        Answer::Number(v as i64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(v: isize) -> Self {
+        Answer::Number(v as i64)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(v: i32) -> Self {
+        Answer::Number(v.into())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Answer::Unsolved, Into::into)
+    }
+}
+
+pub trait Solution {
+    fn day(&self) -> u8;
+
+    // Puzzle input bundled with the repo
+    fn input(&self) -> &'static str;
+
+    fn part1(&self, input: &str) -> Result<Answer, ParseError>;
+
+    fn part2(&self, _input: &str) -> Result<Answer, ParseError> {
+        Ok(Answer::Unsolved)
+    }
+}
+
+pub fn registry() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::new(day1::Day1),
+        Box::new(day2::Day2),
+        Box::new(day3::Day3),
+        Box::new(day4::Day4),
+        Box::new(day5::Day5),
+        Box::new(day6::Day6),
+        Box::new(day7::Day7),
+        Box::new(day8::Day8),
+        Box::new(day9::Day9),
+        Box::new(day10::Day10),
+        Box::new(day11::Day11),
+        Box::new(day12::Day12),
+        Box::new(day13::Day13),
+    ]
+}
+
+// Runs one part, turning a panic into an error so one broken day doesn't stop the others
+pub fn run_part(solution: &dyn Solution, part: u8, input: &str) -> Result<Answer, String> {
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| match part {
+        1 => solution.part1(input),
+        _ => solution.part2(input),
+    }))
+    .map_err(|e| {
+        e.downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())
+    })?;
+    Ok(answer?)
+}
+
+pub fn find(day: u8) -> Option<Box<dyn Solution>> {
+    registry()
+        .into_iter()
+        .find(|solution| solution.day() == day)
+}
+
+#[test]
+fn test_registry_days() {
+    let days = registry().iter().map(|s| s.day()).collect::<Vec<_>>();
+    assert_eq!(days, (1..=13).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_answer_from() {
+    assert_eq!(Answer::from(Some(3_usize)), Answer::Number(3));
+    assert_eq!(Answer::from(None::<i32>), Answer::Unsolved);
+    assert_eq!(Answer::from(-2_isize).to_string(), "-2");
+}
@@ -0,0 +1,190 @@
+use crate::input::InputProvider;
+use crate::solution::{self, Answer, Solution};
+use std::collections::BTreeMap;
+
+// The answers.toml at the repo root, used when `--answers` isn't given
+pub const ANSWERS: &str = include_str!("../answers.toml");
+
+// Expected answer per (day, part), kept as the text `Answer` displays as
+pub type Answers = BTreeMap<(u8, u8), String>;
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    Mismatch,
+    // No entry in the answers file, the actual answer is only shown
+    Unknown,
+    // Panicked or the input didn't parse
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub expected: Option<String>,
+    pub actual: String,
+    pub status: Status,
+}
+
+// The line up to a `#` that isn't inside a "..." string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// Just enough TOML for answers.toml: `[dayN]` tables of `partN = 123` or `partN = "text"`
+pub fn parse_answers(toml: &str) -> Result<Answers, String> {
+    let mut answers = BTreeMap::new();
+    let mut day = None;
+
+    for (i, line) in toml.lines().enumerate() {
+        let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(table) = line.strip_prefix('[') {
+            let n = table
+                .strip_suffix(']')
+                .and_then(|t| t.strip_prefix("day"))
+                .and_then(|n| n.parse::<u8>().ok())
+                .ok_or_else(|| error("expected a [dayN] table"))?;
+            day = Some(n);
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected key = value"))?;
+        let part = match key.trim() {
+            "part1" => 1,
+            "part2" => 2,
+            key => return Err(error(&format!("unknown key {}", key))),
+        };
+        let value = value.trim();
+        let value = if value.starts_with('"') {
+            value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .ok_or_else(|| error("unterminated string"))?
+                .replace("\\n", "\n")
+        } else {
+            value
+                .parse::<i64>()
+                .map_err(|_| error(&format!("invalid number {}", value)))?
+                .to_string()
+        };
+        let day = day.ok_or_else(|| error("key outside a [dayN] table"))?;
+        answers.insert((day, part), value);
+    }
+    Ok(answers)
+}
+
+pub fn check(solution: &dyn Solution, part: u8, input: &str, answers: &Answers) -> Check {
+    let expected = answers.get(&(solution.day(), part)).cloned();
+    let (actual, status) = match solution::run_part(solution, part, input) {
+        Err(e) => (e, Status::Failed),
+        Ok(answer) => {
+            let status = match &expected {
+                None => Status::Unknown,
+                Some(expected) if *expected == answer.to_string() => Status::Ok,
+                Some(_) => Status::Mismatch,
+            };
+            (answer.to_string(), status)
+        }
+    };
+    Check {
+        day: solution.day(),
+        part,
+        expected,
+        actual,
+        status,
+    }
+}
+
+// Every solved part of every registered day
+pub fn verify(inputs: &InputProvider, answers: &Answers) -> Result<Vec<Check>, String> {
+    let mut checks = vec![];
+    for solution in solution::registry() {
+        let input = inputs.load(solution.as_ref())?;
+        for part in 1..=2 {
+            let check = check(solution.as_ref(), part, &input, answers);
+            if check.actual != Answer::Unsolved.to_string() || check.expected.is_some() {
+                checks.push(check);
+            }
+        }
+    }
+    Ok(checks)
+}
+
+pub fn is_failure(check: &Check) -> bool {
+    check.status == Status::Mismatch || check.status == Status::Failed
+}
+
+pub fn table(checks: &[Check]) -> String {
+    let summary = |s: &str| Answer::Text(s.to_string()).summary();
+    let mut table = format!(
+        "{:>3} | {:>4} | {:>16} | {:>16} | {}\n",
+        "day", "part", "expected", "actual", "status"
+    );
+    for c in checks {
+        let status = match c.status {
+            Status::Ok => "ok",
+            Status::Mismatch => "MISMATCH",
+            Status::Unknown => "no answer",
+            Status::Failed => "FAILED",
+        };
+        table += &format!(
+            "{:>3} | {:>4} | {:>16} | {:>16} | {}\n",
+            c.day,
+            c.part,
+            c.expected.as_deref().map_or("-".to_string(), summary),
+            summary(&c.actual),
+            status
+        );
+    }
+    table
+}
+
+#[test]
+fn test_parse_answers() {
+    let answers = parse_answers("# comment\n[day1]\npart1 = 42\n\n[day11]\npart2 = \"AB\\nCD\"\n");
+    assert_eq!(answers.as_ref().map(|a| a.len()), Ok(2));
+    assert_eq!(answers.as_ref().unwrap()[&(1, 1)], "42");
+    assert_eq!(answers.as_ref().unwrap()[&(11, 2)], "AB\nCD");
+    // A `#` in a string is part of the answer, after it starts a comment
+    let answers = parse_answers("[day8]\npart2 = \"#..#\\n.##.\" # picture\n");
+    assert_eq!(answers.unwrap()[&(8, 2)], "#..#\n.##.");
+    assert!(parse_answers("part1 = 1").is_err());
+    assert!(parse_answers("[day1]\npart3 = 1").is_err());
+    assert!(parse_answers("[day1]\npart1 = x").is_err());
+}
+
+// The test `answer_tests!` generates for one day, naming each part that fails
+#[cfg(test)]
+pub fn check_answers(solution: &dyn Solution) {
+    let answers = parse_answers(ANSWERS).unwrap();
+    let input = InputProvider::new(None, None).load(solution).unwrap();
+    let failures = (1..=2)
+        .map(|part| check(solution, part, &input, &answers))
+        .filter(is_failure)
+        .map(|c| {
+            format!(
+                "day {} part {}: expected {}, got {}",
+                c.day,
+                c.part,
+                c.expected.as_deref().unwrap_or("-"),
+                c.actual
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}